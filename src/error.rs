@@ -3,9 +3,19 @@
 pub enum ErrorKind {
     /// Error when data is invalid.
     InvalidData,
-    // Error when input is invalid.
+    /// Error when input is invalid.
     InvalidInput,
-    // Error when unknown.
+    /// Error when a hex color string is malformed.
+    InvalidHex,
+    /// Error when the system accent color cannot be retrieved.
+    InvalidAccent,
+    /// Error when an `rgb()`, `rgba()`, `hsl()` or `hsla()` color is malformed.
+    InvalidRgb,
+    /// Error when a gradient direction or its coordinates are invalid.
+    InvalidGradientCoordinates,
+    /// Error when Direct2D fails to create a brush or gradient stop collection.
+    BrushCreationFailed,
+    /// Error when the cause is unknown.
     InvalidUnknown,
 }
 
@@ -18,14 +28,23 @@ impl core::fmt::Display for ErrorKind {
             Self::InvalidUnknown => write!(f, "invalid unknown format"),
             Self::InvalidInput => write!(f, "invalid input"),
             Self::InvalidData => write!(f, "invalid data"),
+            Self::InvalidHex => write!(f, "invalid hex color"),
+            Self::InvalidAccent => write!(f, "invalid accent color"),
+            Self::InvalidRgb => write!(f, "invalid rgb color"),
+            Self::InvalidGradientCoordinates => write!(f, "invalid gradient coordinates"),
+            Self::BrushCreationFailed => write!(f, "brush creation failed"),
         }
     }
 }
 
+/// An error that occurred while parsing a color or creating a brush.
+///
+/// Errors compare, hash and order by their `ErrorKind` only.
 #[derive(Clone)]
 pub struct Error {
     kind: ErrorKind,
     message: String,
+    source: Option<windows::core::Error>,
 }
 
 impl core::fmt::Debug for Error {
@@ -34,6 +53,7 @@ impl core::fmt::Debug for Error {
         debug
             .field("kind", &self.kind())
             .field("message", &self.message())
+            .field("source", &self.source)
             .finish()
     }
 }
@@ -66,7 +86,7 @@ impl Ord for Error {
 
 impl Error {
     /// Creates a new `Error` instance with a specified kind and a message.
-    /// If the message is empty, it will use an empty message for the error.
+    /// If the message is empty, only the error kind is displayed.
     ///
     /// # Parameters
     /// - `kind`: The type of error (e.g., `InvalidGradientCoordinates`, `InvalidAccent`).
@@ -79,13 +99,33 @@ impl Error {
         Self {
             kind,
             message: message.to_string(),
+            source: None,
+        }
+    }
+
+    /// Creates a new `Error` instance wrapping a failed Windows API call.
+    ///
+    /// The message contains the HRESULT of the failure, and the original error is available
+    /// through `source()`.
+    ///
+    /// # Parameters
+    /// - `kind`: The type of error (e.g., `BrushCreationFailed`).
+    /// - `source`: The error returned by the Windows API.
+    ///
+    /// # Returns
+    /// A new `Error` instance with the specified error kind and source.
+    pub(crate) fn from_windows(kind: ErrorKind, source: windows::core::Error) -> Self {
+        Self {
+            kind,
+            message: format!("{} (HRESULT {:#010X})", source.message(), source.code().0),
+            source: Some(source),
         }
     }
 
     /// Retrieves the kind of the error.
     ///
     /// # Returns
-    /// A reference to the `ErrorKind` variant that represents the type of error.
+    /// The `ErrorKind` variant that represents the type of error.
     pub fn kind(&self) -> ErrorKind {
         self.kind.clone()
     }
@@ -93,10 +133,18 @@ impl Error {
     /// Retrieves the error message, if provided.
     ///
     /// # Returns
-    /// The error message as a string slice. If no message is provided, an empty string is returned.
+    /// The error message as a `String`. If no message is provided, an empty string is returned.
     pub fn message(&self) -> String {
         self.message.clone()
     }
+
+    /// Retrieves the HRESULT of the Windows API call that caused the error, if any.
+    ///
+    /// # Returns
+    /// The HRESULT for errors such as `BrushCreationFailed`, or `None` for parsing errors.
+    pub fn hresult(&self) -> Option<windows::core::HRESULT> {
+        self.source.as_ref().map(|source| source.code())
+    }
 }
 
 impl core::fmt::Display for Error {
@@ -113,9 +161,73 @@ impl core::fmt::Display for Error {
     }
 }
 
-impl std::error::Error for Error {}
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.source
+            .as_ref()
+            .map(|source| source as &(dyn std::error::Error + 'static))
+    }
+}
 
 /// A custom `Result` type that returns `Error` in case of failure.
 ///
-/// This type is used for handling errors related to shell operations. It wraps the standard `Result` type but replaces the error type with our custom `Error` type.
+/// This type is used for handling errors related to parsing colors and creating brushes. It wraps the standard `Result` type but replaces the error type with our custom `Error` type.
 pub type Result<T> = core::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error as _;
+    use windows::Win32::Foundation::E_FAIL;
+
+    #[test]
+    fn windows_errors_carry_hresult_and_source() {
+        let error = Error::from_windows(
+            ErrorKind::BrushCreationFailed,
+            windows::core::Error::from_hresult(E_FAIL),
+        );
+
+        assert_eq!(error.kind(), ErrorKind::BrushCreationFailed);
+        assert_eq!(error.hresult(), Some(E_FAIL));
+        assert!(error.message().contains("0x80004005"), "{}", error);
+
+        let source = error.source().unwrap();
+        let source = source.downcast_ref::<windows::core::Error>().unwrap();
+        assert_eq!(source.code(), E_FAIL);
+    }
+
+    #[test]
+    fn parsing_errors_have_no_source() {
+        let error = Error::new(ErrorKind::InvalidHex, "Invalid hex color: #12");
+
+        assert_eq!(error.hresult(), None);
+        assert!(error.source().is_none());
+    }
+
+    #[test]
+    fn constructs_and_displays_each_kind() {
+        for (kind, display) in [
+            (ErrorKind::InvalidData, "invalid data"),
+            (ErrorKind::InvalidInput, "invalid input"),
+            (ErrorKind::InvalidHex, "invalid hex color"),
+            (ErrorKind::InvalidAccent, "invalid accent color"),
+            (ErrorKind::InvalidRgb, "invalid rgb color"),
+            (
+                ErrorKind::InvalidGradientCoordinates,
+                "invalid gradient coordinates",
+            ),
+            (ErrorKind::BrushCreationFailed, "brush creation failed"),
+            (ErrorKind::InvalidUnknown, "invalid unknown format"),
+        ] {
+            assert_eq!(kind.to_string(), display);
+
+            let error = Error::new(kind.clone(), "");
+            assert_eq!(error.kind(), kind);
+            assert_eq!(error.to_string(), display);
+
+            let error = Error::new(kind.clone(), "details");
+            assert_eq!(error.message(), "details");
+            assert_eq!(error.to_string(), format!("{} (details)", display));
+        }
+    }
+}
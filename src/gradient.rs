@@ -1,32 +1,254 @@
+use serde::de::Error as DeError;
 use serde::Deserialize;
+use serde::Deserializer;
+use serde::Serialize;
 
+use crate::error::Error;
+use crate::error::ErrorKind;
+use crate::error::Result;
+use crate::parser::parse_color_string;
+use crate::parser::parse_gradient_direction;
+use crate::utils::lerp;
+use crate::utils::lerp_color;
+use crate::utils::premultiply;
+use crate::utils::to_hex_rgba;
+use crate::Color;
 use crate::GradientCoordinates;
+use crate::Solid;
+use crate::SolidImpl;
+use windows::core::Interface;
+use windows::Foundation::Numerics::Matrix3x2;
 use windows::Win32::{
     Foundation::RECT,
     Graphics::Direct2D::{
-        Common::{D2D1_GRADIENT_STOP, D2D_POINT_2F},
-        ID2D1LinearGradientBrush,
+        Common::{D2D1_COLOR_F, D2D1_GRADIENT_STOP, D2D_POINT_2F},
+        ID2D1Brush, ID2D1LinearGradientBrush, ID2D1RadialGradientBrush, ID2D1RenderTarget,
+        D2D1_EXTEND_MODE, D2D1_EXTEND_MODE_CLAMP, D2D1_EXTEND_MODE_MIRROR, D2D1_EXTEND_MODE_WRAP,
+        D2D1_GAMMA, D2D1_GAMMA_1_0, D2D1_GAMMA_2_2,
     },
 };
 
-#[allow(dead_code)]
 pub trait GradientImpl {
     /// Updates the start and end points of the gradient based on the window's dimensions.
     ///
     /// This method calculates the new start and end points of the gradient brush based on the
     /// window's size (given by `window_rect`). The direction of the gradient is scaled from
     /// normalized coordinates (ranging from 0.0 to 1.0) to pixel coordinates based on the window's
-    /// width and height.
+    /// width and height. For radial gradients, the start point is used as the center and the
+    /// distance to the end point as the radii. Conic gradients are approximated with a linear
+    /// sweep through the center, see `GradientShape::Conic`.
     ///
     /// # Parameters
     /// - `window_rect`: The dimensions of the window as a `RECT`, used to compute the pixel
     ///   positions for the gradient's start and end points.
     fn update_start_end_points(&self, window_rect: &RECT);
+
+    /// Reverses the gradient in place.
+    ///
+    /// Every stop position is remapped to `1.0 - position` and the stops are re-sorted, so the
+    /// colors run from the end point back to the start point. The direction itself is left
+    /// unchanged, so the center of radial and conic gradients stays in place and radial
+    /// gradients run from the outside in. The brush is cleared so it is recreated on the next call
+    /// to `to_d2d1_brush`.
+    fn reverse(&mut self);
+
+    /// Samples the color the gradient shows at a normalized position along its direction.
+    ///
+    /// The colors of the stops surrounding `t` are interpolated linearly. Positions before the
+    /// first stop or after the last stop take that stop's color. For repeating and mirrored
+    /// gradients, positions are first wrapped or mirrored back into the range between the first
+    /// and last stop, as returned by `Gradient::repeat_span`.
+    ///
+    /// # Parameters
+    /// - `t`: The position along the gradient, where 0.0 is the start and 1.0 is the end.
+    ///
+    /// # Returns
+    /// The color at `t`. A gradient with a single stop always returns that stop's color, and a
+    /// gradient without stops returns transparent black.
+    fn color_at(&self, t: f32) -> D2D1_COLOR_F;
+
+    /// Iterates over the gradient stops in order.
+    ///
+    /// # Returns
+    /// An iterator yielding each stop's position and a `Solid` wrapping its color, with no brush.
+    fn stops(&self) -> impl Iterator<Item = (f32, Solid)> + '_;
+
+    /// Clamps every stop position to 0.0 to 1.0 and sorts the stops by position.
+    ///
+    /// The sort is stable, so stops sharing a position keep their order and hard color edges are
+    /// preserved. Gradients created by this crate are already normalized.
+    fn normalize_stops(&mut self);
+
+    /// Blends this gradient with another gradient into a single static gradient.
+    ///
+    /// Each stop's color and position are interpolated with the matching stop of `other`, as are
+    /// the direction coordinates and the opacity. The shape, extend mode and gamma are taken from
+    /// this gradient.
+    ///
+    /// # Parameters
+    /// - `other`: The gradient to blend towards.
+    /// - `t`: The blend factor, clamped to 0.0 (this gradient) to 1.0 (`other`).
+    ///
+    /// # Returns
+    /// A `Result` containing the blended `Gradient` with no brush, or an `ErrorKind::InvalidData`
+    /// error if the gradients have different numbers of stops.
+    fn blend(&self, other: &Gradient, t: f32) -> Result<Gradient>;
+
+    /// Computes the average color shown along the gradient.
+    ///
+    /// The average is taken over the positions 0.0 to 1.0, so each stop is weighted by the span it
+    /// covers rather than counted equally. Between two stops the colors blend linearly, and before
+    /// the first stop or after the last stop the color is clamped to that stop.
+    ///
+    /// # Returns
+    /// The average color. A gradient with a single stop returns that stop's color, and a gradient
+    /// without stops returns transparent black.
+    fn average_color(&self) -> D2D1_COLOR_F;
+
+    /// Replaces the gradient stops.
+    ///
+    /// The stops are sorted by position and the brush is cleared, so it is recreated on the next
+    /// call to `to_d2d1_brush`. The gradient is left unchanged if the stops are invalid.
+    ///
+    /// # Parameters
+    /// - `stops`: The new stops as `(position, color)` pairs, in any order.
+    ///
+    /// # Returns
+    /// A `Result` indicating success, or an `ErrorKind::InvalidData` error if fewer than two stops
+    /// are given or a position lies outside 0.0 to 1.0.
+    fn set_stops(&mut self, stops: Vec<(f32, D2D1_COLOR_F)>) -> Result<()>;
+
+    /// Maps every stop position linearly from 0.0 to 1.0 into the range `start` to `end`.
+    ///
+    /// The colors of the first and last stops extend to the edges, so for example remapping into
+    /// 0.25 to 0.75 places the gradient in the middle half. The brush is cleared so it is
+    /// recreated on the next call to `to_d2d1_brush`.
+    ///
+    /// # Parameters
+    /// - `start`: The position the start of the gradient is mapped to.
+    /// - `end`: The position the end of the gradient is mapped to.
+    ///
+    /// # Returns
+    /// A `Result` indicating success, or an `ErrorKind::InvalidData` error if `start` is not less
+    /// than `end` or either lies outside 0.0 to 1.0.
+    fn remap_positions(&mut self, start: f32, end: f32) -> Result<()>;
+
+    /// Caps the number of gradient stops by resampling the gradient.
+    ///
+    /// When the gradient has more than `max_stops` stops, they are replaced with `max_stops`
+    /// evenly spaced stops sampled with `color_at`, and the brush is cleared so it is recreated on
+    /// the next call to `to_d2d1_brush`. Hard color edges between the samples are lost. Gradients
+    /// within the cap are left unchanged.
+    ///
+    /// # Parameters
+    /// - `max_stops`: The maximum number of stops to keep.
+    ///
+    /// # Returns
+    /// A `Result` indicating success, or an `ErrorKind::InvalidInput` error if `max_stops` is less
+    /// than 2.
+    fn quantize(&mut self, max_stops: usize) -> Result<()>;
+
+    /// Checks whether two gradients render identically, for caching.
+    ///
+    /// Stop colors are compared with premultiplied alpha, so fully transparent stops are equal
+    /// regardless of their red, green and blue channels. Stop colors, positions, the direction
+    /// coordinates and the opacity are compared within half an 8-bit step (0.5 / 255), while the
+    /// shape, extend mode and gamma must match exactly. Brushes and transforms are ignored.
+    ///
+    /// # Parameters
+    /// - `other`: The gradient to compare with.
+    ///
+    /// # Returns
+    /// `true` if the gradients are visually equal.
+    fn visually_eq(&self, other: &Gradient) -> bool;
+
+    /// Removes stops that repeat the previous stop.
+    ///
+    /// A stop is removed when it has exactly the same color as its predecessor and a position
+    /// within 0.0001 of it, which can happen after `quantize` or easing. At least two stops are
+    /// always kept. If any stop is removed, the brush is cleared so it is recreated on the next
+    /// call to `to_d2d1_brush`.
+    fn dedup_stops(&mut self);
+
+    /// Checks whether every stop has the same color, so the gradient renders as a solid color.
+    ///
+    /// Colors are compared channel by channel within half an 8-bit step (0.5 / 255) of the first
+    /// stop's color. Stop positions, the direction and the extend mode don't matter, since a
+    /// single color looks the same everywhere.
+    ///
+    /// # Returns
+    /// `true` if the gradient has at least one stop and all stops share the same color.
+    fn is_effectively_solid(&self) -> bool;
+}
+
+/// How a gradient is extended beyond its start and end points.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum GradientExtendMode {
+    /// The colors of the first and last stops extend past the ends of the gradient.
+    #[default]
+    Clamp,
+    /// The gradient repeats, as with `repeating-linear-gradient(...)` and the other `repeating-`
+    /// gradient functions.
+    Wrap,
+    /// The gradient repeats, reversing every other repetition.
+    Mirror,
+}
+
+impl From<GradientExtendMode> for D2D1_EXTEND_MODE {
+    fn from(extend_mode: GradientExtendMode) -> Self {
+        match extend_mode {
+            GradientExtendMode::Clamp => D2D1_EXTEND_MODE_CLAMP,
+            GradientExtendMode::Wrap => D2D1_EXTEND_MODE_WRAP,
+            GradientExtendMode::Mirror => D2D1_EXTEND_MODE_MIRROR,
+        }
+    }
+}
+
+/// The gamma space in which a gradient's colors are interpolated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum GradientGamma {
+    /// Interpolation in the gamma 2.2 (sRGB) color space, matching CSS gradients.
+    #[default]
+    Srgb,
+    /// Interpolation in the gamma 1.0 (linear light) color space, which avoids dark bands between
+    /// saturated colors.
+    Linear,
+}
+
+impl From<GradientGamma> for D2D1_GAMMA {
+    fn from(gamma: GradientGamma) -> Self {
+        match gamma {
+            GradientGamma::Srgb => D2D1_GAMMA_2_2,
+            GradientGamma::Linear => D2D1_GAMMA_1_0,
+        }
+    }
+}
+
+/// The shape of a gradient, which determines the kind of Direct2D brush created for it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum GradientShape {
+    /// A linear gradient running from the start point to the end point.
+    #[default]
+    Linear,
+    /// A radial gradient centered on the start point, with radii given by the distance from the
+    /// start point to the end point.
+    Radial,
+    /// A conic gradient centered on the start point, starting at the angle of the end point.
+    ///
+    /// Direct2D has no conic gradient brush, so conic gradients are approximated by a linear
+    /// gradient that sweeps through the center along the starting angle. The colors therefore
+    /// change along that axis rather than around the center, and a full turn cannot be
+    /// represented.
+    ///
+    /// No angular stops are synthesized for the approximation: linear and radial brushes keep a
+    /// constant color along lines or circles, so no number of extra stops makes the color vary
+    /// around the center. The authored stops are used as is, in sweep order.
+    Conic,
 }
 
 /// Represents a gradient with a specific direction, gradient stops, and an optional brush.
 ///
-/// The `Gradient` struct defines a linear gradient that can be applied to render objects with
+/// The `Gradient` struct defines a linear or radial gradient that can be applied to render objects with
 /// smooth transitions between colors. The gradient's direction and color stops determine how the
 /// gradient appears, while the optional brush holds the gradient data for rendering.
 ///
@@ -37,40 +259,101 @@ pub trait GradientImpl {
 ///   start and end points.
 /// - `gradient_stops`: A vector of `D2D1_GRADIENT_STOP` values, representing the color stops in the
 ///   gradient. These stops define the colors that the gradient transitions through.
-/// - `brush`: An optional `ID2D1LinearGradientBrush` used to render the gradient. If not initialized,
-///   this value is `None`.
+/// - `shape`: The `GradientShape` of the gradient, either linear (the default) or radial.
+/// - `extend_mode`: The `GradientExtendMode` used beyond the start and end points, clamping by
+///   default. Repeating and mirrored gradients repeat between their first and last stop, like CSS
+///   repeating gradients.
+/// - `gamma`: The `GradientGamma` the stops are interpolated in, sRGB (gamma 2.2) by default.
+/// - `opacity`: The opacity applied to the brush when it is created, ranging from 0.0 to 1.0. It
+///   multiplies with the opacity of the brush properties, and defaults to 1.0.
+/// - `transform`: An optional transform applied to the brush whenever it is created.
+/// - `brush`: An optional `ID2D1Brush` used to render the gradient. This is either an
+///   `ID2D1LinearGradientBrush` or an `ID2D1RadialGradientBrush` depending on `shape`. If not
+///   initialized, this value is `None`.
 ///
 /// # Example
 /// ```rust
-/// use windows::Win32::Graphics::Direct2D::Common::D2D1_GRADIENT_STOP;
-/// let gradient = Gradient {
-///     direction: GradientCoordinates { start: [0.0, 0.0], end: [1.0, 1.0] },
-///     gradient_stops: vec![
-///         D2D1_GRADIENT_STOP { position: 0.0, color: D2D1_COLOR_F { r: 1.0, g: 0.0, b: 0.0, a: 1.0 } },
-///         D2D1_GRADIENT_STOP { position: 1.0, color: D2D1_COLOR_F { r: 0.0, g: 0.0, b: 1.0, a: 1.0 } },
-///     ],
-///     brush: None, // Brush will be initialized later
-/// };
+/// use windows::Win32::Graphics::Direct2D::Common::D2D1_COLOR_F;
+/// # use win_color::{GradientBuilder, GradientCoordinates, GradientDirection};
+/// # fn main() -> win_color::Result<()> {
+/// let gradient = GradientBuilder::new()
+///     .add_stop(0.0, D2D1_COLOR_F { r: 1.0, g: 0.0, b: 0.0, a: 1.0 })
+///     .add_stop(1.0, D2D1_COLOR_F { r: 0.0, g: 0.0, b: 1.0, a: 1.0 })
+///     .direction(GradientDirection::Coordinates(GradientCoordinates {
+///         start: [0.0, 0.0],
+///         end: [1.0, 1.0],
+///     }))
+///     .build()?; // Brush will be initialized later
+/// # Ok(())
+/// # }
 /// ```
-#[derive(Debug, Clone, PartialEq)]
+///
+/// Two `Gradient`s are equal when their direction, stops, shape, extend mode, gamma, opacity and
+/// transform are equal.
+/// The cached `brush` is ignored, so a gradient compares equal to a copy whose brush has not been
+/// created yet.
+#[derive(Debug, Clone)]
 pub struct Gradient {
     /// The direction of the gradient, either as a string or as coordinates.
     pub direction: GradientCoordinates,
     /// A list of gradient stops defining color stops in the gradient.
     pub gradient_stops: Vec<D2D1_GRADIENT_STOP>,
+    /// The shape of the gradient, either linear or radial.
+    pub shape: GradientShape,
+    /// How the gradient extends beyond its start and end points.
+    pub extend_mode: GradientExtendMode,
+    /// The gamma space in which the colors are interpolated.
+    pub gamma: GradientGamma,
+    /// The authored opacity of the brush, such as the `opacity` of a `ColorMapping`.
+    pub opacity: f32,
+    /// The authored transform of the brush. When set, it replaces the transform of the brush
+    /// properties each time the brush is created, so it survives brush recreation.
+    pub transform: Option<Matrix3x2>,
 
-    /// An optional linear gradient brush that can be used for rendering the gradient.
+    /// An optional gradient brush that can be used for rendering the gradient.
     /// It represents the gradient with a direction and color stops, and may be `None` if not yet initialized.
-    pub brush: Option<ID2D1LinearGradientBrush>,
+    pub brush: Option<ID2D1Brush>,
+    /// A hash of the inputs the `brush` was created from, used to reuse the brush when they
+    /// haven't changed.
+    pub(crate) brush_key: Option<u64>,
+    /// The render target the `brush` was created on. It is kept alive with the brush, so the
+    /// brush is only reused on that same target.
+    pub(crate) brush_target: Option<ID2D1RenderTarget>,
 }
 
-impl GradientImpl for Gradient {
-    fn update_start_end_points(&self, window_rect: &RECT) {
+impl Gradient {
+    /// Creates a gradient without a brush, normalizing its stops with `normalize_stops`.
+    pub(crate) fn new(
+        direction: GradientCoordinates,
+        gradient_stops: Vec<D2D1_GRADIENT_STOP>,
+        shape: GradientShape,
+    ) -> Gradient {
+        let mut gradient = Gradient {
+            direction,
+            gradient_stops,
+            shape,
+            extend_mode: GradientExtendMode::Clamp,
+            gamma: GradientGamma::Srgb,
+            opacity: 1.0,
+            transform: None,
+            brush: None,
+            brush_key: None,
+            brush_target: None,
+        };
+        gradient.normalize_stops();
+        gradient
+    }
+
+    /// Converts the gradient direction into the pixel start and end points of the brush.
+    ///
+    /// The direction/GradientCoordinates only range from 0.0 to 1.0, so they are scaled by the
+    /// window's width and height. For conic gradients, the start point is mirrored through the
+    /// center so that the linear approximation sweeps across the whole window. Both points are
+    /// then moved onto the `repeat_span` of the gradient.
+    pub(crate) fn pixel_points(&self, window_rect: &RECT) -> (D2D_POINT_2F, D2D_POINT_2F) {
         let width = (window_rect.right - window_rect.left) as f32;
         let height = (window_rect.bottom - window_rect.top) as f32;
 
-        // The direction/GradientCoordinates only range from 0.0 to 1.0, but we need to
-        // convert it into coordinates in terms of pixels
         let start_point = D2D_POINT_2F {
             x: self.direction.start[0] * width,
             y: self.direction.start[1] * height,
@@ -80,24 +363,753 @@ impl GradientImpl for Gradient {
             y: self.direction.end[1] * height,
         };
 
-        if let Some(ref id2d1_brush) = self.brush {
-            unsafe {
-                id2d1_brush.SetStartPoint(start_point);
-                id2d1_brush.SetEndPoint(end_point)
-            };
+        let (start_point, end_point) = match self.shape {
+            GradientShape::Conic => (
+                D2D_POINT_2F {
+                    x: 2.0 * start_point.x - end_point.x,
+                    y: 2.0 * start_point.y - end_point.y,
+                },
+                end_point,
+            ),
+            _ => (start_point, end_point),
+        };
+
+        let (from, to) = self.repeat_span();
+        let point_at = |t: f32| D2D_POINT_2F {
+            x: start_point.x + (end_point.x - start_point.x) * t,
+            y: start_point.y + (end_point.y - start_point.y) * t,
+        };
+
+        (point_at(from), point_at(to))
+    }
+
+    /// Returns the range of stop positions the brush repeats over.
+    ///
+    /// Clamped gradients span the whole 0.0 to 1.0 range. Repeating and mirrored gradients repeat
+    /// between their first and last stop, like CSS repeating gradients, so the brush points are
+    /// moved onto those stops by `pixel_points` and the stops are stretched over them by
+    /// `brush_stops`. Radial gradients keep their center, so they always repeat from 0.0.
+    pub(crate) fn repeat_span(&self) -> (f32, f32) {
+        let (Some(first), Some(last)) = (self.gradient_stops.first(), self.gradient_stops.last())
+        else {
+            return (0.0, 1.0);
+        };
+
+        let from = match self.shape {
+            GradientShape::Radial => 0.0,
+            _ => first.position,
+        };
+
+        if self.extend_mode == GradientExtendMode::Clamp || last.position - from <= f32::EPSILON {
+            (0.0, 1.0)
+        } else {
+            (from, last.position)
+        }
+    }
+
+    /// Returns the stops passed to Direct2D, with their positions stretched over the
+    /// `repeat_span` of the gradient.
+    pub(crate) fn brush_stops(&self) -> Vec<D2D1_GRADIENT_STOP> {
+        let (from, to) = self.repeat_span();
+
+        self.gradient_stops
+            .iter()
+            .map(|stop| D2D1_GRADIENT_STOP {
+                position: (stop.position - from) / (to - from),
+                color: stop.color,
+            })
+            .collect()
+    }
+}
+
+impl PartialEq for Gradient {
+    fn eq(&self, other: &Self) -> bool {
+        self.direction == other.direction
+            && self.gradient_stops == other.gradient_stops
+            && self.shape == other.shape
+            && self.extend_mode == other.extend_mode
+            && self.gamma == other.gamma
+            && self.opacity == other.opacity
+            && self.transform == other.transform
+    }
+}
+
+impl GradientImpl for Gradient {
+    fn update_start_end_points(&self, window_rect: &RECT) {
+        let (start_point, end_point) = self.pixel_points(window_rect);
+
+        let Some(ref id2d1_brush) = self.brush else {
+            return;
+        };
+
+        match self.shape {
+            GradientShape::Linear | GradientShape::Conic => {
+                if let Ok(linear_brush) = id2d1_brush.cast::<ID2D1LinearGradientBrush>() {
+                    unsafe {
+                        linear_brush.SetStartPoint(start_point);
+                        linear_brush.SetEndPoint(end_point)
+                    };
+                }
+            }
+            GradientShape::Radial => {
+                if let Ok(radial_brush) = id2d1_brush.cast::<ID2D1RadialGradientBrush>() {
+                    unsafe {
+                        radial_brush.SetCenter(start_point);
+                        radial_brush.SetRadiusX((end_point.x - start_point.x).abs());
+                        radial_brush.SetRadiusY((end_point.y - start_point.y).abs());
+                    };
+                }
+            }
+        }
+    }
+
+    fn reverse(&mut self) {
+        self.gradient_stops.reverse();
+        for stop in self.gradient_stops.iter_mut() {
+            stop.position = 1.0 - stop.position;
+        }
+        self.normalize_stops();
+
+        self.brush = None;
+        self.brush_key = None;
+        self.brush_target = None;
+    }
+
+    fn color_at(&self, t: f32) -> D2D1_COLOR_F {
+        let (from, to) = self.repeat_span();
+        let span = to - from;
+        let t = match self.extend_mode {
+            GradientExtendMode::Wrap if !(from..=to).contains(&t) => {
+                from + (t - from).rem_euclid(span)
+            }
+            GradientExtendMode::Mirror => {
+                let t = (t - from).rem_euclid(2.0 * span);
+                if t > span {
+                    to - (t - span)
+                } else {
+                    from + t
+                }
+            }
+            _ => t,
+        };
+
+        let stops = &self.gradient_stops;
+        let (Some(first), Some(last)) = (stops.first(), stops.last()) else {
+            return D2D1_COLOR_F::default();
+        };
+
+        if t <= first.position {
+            return first.color;
+        }
+
+        if t >= last.position {
+            return last.color;
+        }
+
+        stops
+            .windows(2)
+            .find(|pair| t <= pair[1].position)
+            .map(|pair| {
+                let span = pair[1].position - pair[0].position;
+                let local_t = if span > 0.0 {
+                    (t - pair[0].position) / span
+                } else {
+                    1.0
+                };
+                lerp_color(&pair[0].color, &pair[1].color, local_t)
+            })
+            .unwrap_or(last.color)
+    }
+
+    fn stops(&self) -> impl Iterator<Item = (f32, Solid)> + '_ {
+        self.gradient_stops
+            .iter()
+            .map(|stop| (stop.position, Solid::from(stop.color)))
+    }
+
+    fn normalize_stops(&mut self) {
+        for stop in self.gradient_stops.iter_mut() {
+            stop.position = stop.position.clamp(0.0, 1.0);
+        }
+
+        self.gradient_stops
+            .sort_by(|a, b| a.position.total_cmp(&b.position));
+    }
+
+    fn blend(&self, other: &Gradient, t: f32) -> Result<Gradient> {
+        let t = t.clamp(0.0, 1.0);
+
+        if self.gradient_stops.len() != other.gradient_stops.len() {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "Cannot interpolate gradients with {} and {} stops",
+                    self.gradient_stops.len(),
+                    other.gradient_stops.len()
+                ),
+            ));
+        }
+
+        let gradient_stops = self
+            .gradient_stops
+            .iter()
+            .zip(other.gradient_stops.iter())
+            .map(|(from, to)| D2D1_GRADIENT_STOP {
+                position: lerp(from.position, to.position, t),
+                color: lerp_color(&from.color, &to.color, t),
+            })
+            .collect();
+
+        let (from, to) = (&self.direction, &other.direction);
+        let direction = GradientCoordinates {
+            start: [
+                lerp(from.start[0], to.start[0], t),
+                lerp(from.start[1], to.start[1], t),
+            ],
+            end: [
+                lerp(from.end[0], to.end[0], t),
+                lerp(from.end[1], to.end[1], t),
+            ],
+        };
+
+        Ok(Gradient {
+            extend_mode: self.extend_mode,
+            gamma: self.gamma,
+            opacity: lerp(self.opacity, other.opacity, t),
+            ..Gradient::new(direction, gradient_stops, self.shape)
+        })
+    }
+
+    fn average_color(&self) -> D2D1_COLOR_F {
+        let stops = &self.gradient_stops;
+        let (Some(first), Some(last)) = (stops.first(), stops.last()) else {
+            return D2D1_COLOR_F::default();
+        };
+
+        if stops.len() == 1 {
+            return first.color;
+        }
+
+        // Each span contributes its width times its mean color.
+        let mut spans = vec![
+            (first.position.clamp(0.0, 1.0), first.color),
+            (1.0 - last.position.clamp(0.0, 1.0), last.color),
+        ];
+        spans.extend(stops.windows(2).map(|pair| {
+            let width = pair[1].position - pair[0].position;
+            (width, lerp_color(&pair[0].color, &pair[1].color, 0.5))
+        }));
+
+        let total: f32 = spans.iter().map(|(width, _)| width).sum();
+        if total <= 0.0 {
+            return first.color;
+        }
+
+        spans
+            .iter()
+            .fold(D2D1_COLOR_F::default(), |sum, (width, color)| {
+                let weight = width / total;
+                D2D1_COLOR_F {
+                    r: sum.r + color.r * weight,
+                    g: sum.g + color.g * weight,
+                    b: sum.b + color.b * weight,
+                    a: sum.a + color.a * weight,
+                }
+            })
+    }
+
+    fn set_stops(&mut self, stops: Vec<(f32, D2D1_COLOR_F)>) -> Result<()> {
+        if stops.len() < 2 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "A gradient requires at least two stops",
+            ));
+        }
+
+        if let Some((position, _)) = stops
+            .iter()
+            .find(|(position, _)| !(0.0..=1.0).contains(position))
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("Gradient stop position out of range: {}", position),
+            ));
+        }
+
+        self.gradient_stops = stops
+            .into_iter()
+            .map(|(position, color)| D2D1_GRADIENT_STOP { position, color })
+            .collect();
+        self.normalize_stops();
+
+        self.brush = None;
+        self.brush_key = None;
+        self.brush_target = None;
+
+        Ok(())
+    }
+
+    fn remap_positions(&mut self, start: f32, end: f32) -> Result<()> {
+        let range = 0.0..=1.0;
+        if !(range.contains(&start) && range.contains(&end) && start < end) {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("Invalid gradient stop range: {} to {}", start, end),
+            ));
+        }
+
+        for stop in self.gradient_stops.iter_mut() {
+            stop.position = lerp(start, end, stop.position);
+        }
+
+        self.brush = None;
+        self.brush_key = None;
+        self.brush_target = None;
+
+        Ok(())
+    }
+
+    fn quantize(&mut self, max_stops: usize) -> Result<()> {
+        if max_stops < 2 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "A gradient requires at least two stops, but {} were allowed",
+                    max_stops
+                ),
+            ));
+        }
+
+        if self.gradient_stops.len() <= max_stops {
+            return Ok(());
+        }
+
+        let step = 1.0 / (max_stops - 1) as f32;
+        self.gradient_stops = (0..max_stops)
+            .map(|i| {
+                let position = i as f32 * step;
+                D2D1_GRADIENT_STOP {
+                    position,
+                    color: self.color_at(position),
+                }
+            })
+            .collect();
+
+        self.brush = None;
+        self.brush_key = None;
+        self.brush_target = None;
+
+        Ok(())
+    }
+
+    fn dedup_stops(&mut self) {
+        let len = self.gradient_stops.len();
+        let mut stops: Vec<D2D1_GRADIENT_STOP> = Vec::with_capacity(len);
+
+        for (i, stop) in self.gradient_stops.iter().enumerate() {
+            let remaining = len - i;
+            let duplicate = stops.last().is_some_and(|previous| {
+                previous.color == stop.color && (previous.position - stop.position).abs() <= 1e-4
+            });
+
+            // Keep the stop anyway if dropping it would leave fewer than two stops.
+            if !duplicate || stops.len() + remaining <= 2 {
+                stops.push(*stop);
+            }
+        }
+
+        if stops.len() != len {
+            self.gradient_stops = stops;
+            self.brush = None;
+            self.brush_key = None;
+            self.brush_target = None;
+        }
+    }
+
+    fn is_effectively_solid(&self) -> bool {
+        let close = |a: f32, b: f32| (a - b).abs() <= 0.5 / 255.0;
+
+        let Some(first) = self.gradient_stops.first() else {
+            return false;
+        };
+
+        self.gradient_stops.iter().all(|stop| {
+            let (a, b) = (&first.color, &stop.color);
+            close(a.r, b.r) && close(a.g, b.g) && close(a.b, b.b) && close(a.a, b.a)
+        })
+    }
+
+    fn visually_eq(&self, other: &Gradient) -> bool {
+        let close = |a: f32, b: f32| (a - b).abs() <= 0.5 / 255.0;
+        let colors_close = |a: &D2D1_COLOR_F, b: &D2D1_COLOR_F| {
+            let (a, b) = (premultiply(*a), premultiply(*b));
+            close(a.r, b.r) && close(a.g, b.g) && close(a.b, b.b) && close(a.a, b.a)
+        };
+
+        let (from, to) = (&self.direction, &other.direction);
+        let direction_close = from
+            .start
+            .iter()
+            .chain(from.end.iter())
+            .zip(to.start.iter().chain(to.end.iter()))
+            .all(|(a, b)| close(*a, *b));
+
+        self.shape == other.shape
+            && self.extend_mode == other.extend_mode
+            && self.gamma == other.gamma
+            && close(self.opacity, other.opacity)
+            && direction_close
+            && self.gradient_stops.len() == other.gradient_stops.len()
+            && self
+                .gradient_stops
+                .iter()
+                .zip(other.gradient_stops.iter())
+                .all(|(a, b)| close(a.position, b.position) && colors_close(&a.color, &b.color))
+    }
+}
+
+pub trait GradientCoordinatesImpl {
+    /// Computes the CSS angle of the start to end vector in degrees.
+    ///
+    /// The angle follows the CSS convention where 0 degrees points up and angles increase
+    /// clockwise, so `to right` is 90 degrees.
+    ///
+    /// # Returns
+    /// The angle normalized to the range 0.0 to 360.0, or 0.0 if the start and end points are the
+    /// same.
+    fn angle_degrees(&self) -> f32;
+
+    /// Creates coordinates from start and end points in pixels.
+    ///
+    /// This is the inverse of the scaling done when a brush is created: the points are relative
+    /// to the top-left corner of the window and are divided by its width and height, then
+    /// clamped to 0.0 to 1.0. An axis along which the window is empty maps to 0.0.
+    ///
+    /// # Parameters
+    /// - `start`: The `[x, y]` pixel point where the gradient starts.
+    /// - `end`: The `[x, y]` pixel point where the gradient ends.
+    /// - `window_rect`: The dimensions of the window.
+    ///
+    /// # Returns
+    /// The normalized `GradientCoordinates`.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use win_color::{GradientCoordinates, GradientCoordinatesImpl};
+    /// # use windows::Win32::Foundation::RECT;
+    /// let rect = RECT { left: 0, top: 0, right: 200, bottom: 100 };
+    /// let coordinates = GradientCoordinates::from_pixels([0.0, 50.0], [200.0, 50.0], &rect);
+    /// assert_eq!(coordinates.start, [0.0, 0.5]);
+    /// ```
+    fn from_pixels(start: [f32; 2], end: [f32; 2], window_rect: &RECT) -> GradientCoordinates;
+}
+
+impl GradientCoordinatesImpl for GradientCoordinates {
+    fn angle_degrees(&self) -> f32 {
+        direction_angle(self)
+    }
+
+    fn from_pixels(start: [f32; 2], end: [f32; 2], window_rect: &RECT) -> GradientCoordinates {
+        let width = (window_rect.right - window_rect.left) as f32;
+        let height = (window_rect.bottom - window_rect.top) as f32;
+        let normalize = |value: f32, size: f32| {
+            if size > 0.0 {
+                (value / size).clamp(0.0, 1.0)
+            } else {
+                0.0
+            }
+        };
+        let point = |[x, y]: [f32; 2]| [normalize(x, width), normalize(y, height)];
+
+        GradientCoordinates {
+            start: point(start),
+            end: point(end),
+        }
+    }
+}
+
+/// Computes the CSS angle of the gradient direction in degrees.
+///
+/// The angle follows the CSS convention where 0 degrees points up and angles increase clockwise.
+/// The result is normalized to the range 0.0 to 360.0.
+pub(crate) fn direction_angle(direction: &GradientCoordinates) -> f32 {
+    let dx = direction.end[0] - direction.start[0];
+    let dy = direction.end[1] - direction.start[1];
+
+    if dx == 0.0 && dy == 0.0 {
+        return 0.0;
+    }
+
+    dx.atan2(-dy).to_degrees().rem_euclid(360.0)
+}
+
+/// Converts a CSS angle in degrees into gradient coordinates.
+///
+/// The gradient line passes through the center of the window, and its length is chosen so that
+/// the corners of the window lie on the 0% and 100% lines, matching CSS `linear-gradient`. Angles
+/// are wrapped into the range 0.0 to 360.0 first.
+pub(crate) fn angle_to_coordinates(degrees: f32) -> GradientCoordinates {
+    let radians = degrees.rem_euclid(360.0).to_radians();
+    let (dx, dy) = (radians.sin(), -radians.cos());
+    let half_length = (dx.abs() + dy.abs()) / 2.0;
+
+    GradientCoordinates {
+        start: [0.5 - dx * half_length, 0.5 - dy * half_length],
+        end: [0.5 + dx * half_length, 0.5 + dy * half_length],
+    }
+}
+
+/// Formats the gradient direction as a CSS-like string.
+///
+/// Directions aligned with an axis are formatted as keywords (e.g. `to right`), and all other
+/// directions are formatted as an angle in degrees (e.g. `45deg`).
+pub(crate) fn format_direction(direction: &GradientCoordinates) -> String {
+    let angle = (direction_angle(direction) * 100.0).round() / 100.0;
+
+    match angle {
+        0.0 | 360.0 => "to top".to_string(),
+        90.0 => "to right".to_string(),
+        180.0 => "to bottom".to_string(),
+        270.0 => "to left".to_string(),
+        _ => format!("{}deg", angle),
+    }
+}
+
+/// A builder for assembling a `Gradient` from individual color stops.
+///
+/// Stops are added in order with `add_stop`, and `build` validates that every position lies
+/// within 0.0 to 1.0 and that positions are sorted in ascending order. If no direction is given,
+/// the gradient runs from left to right.
+///
+/// # Example
+/// ```rust
+/// # use win_color::{GradientBuilder, GradientDirection};
+/// # use windows::Win32::Graphics::Direct2D::Common::D2D1_COLOR_F;
+/// # fn main() -> win_color::Result<()> {
+/// let gradient = GradientBuilder::new()
+///     .add_stop(0.0, D2D1_COLOR_F { r: 1.0, g: 0.0, b: 0.0, a: 1.0 })
+///     .add_stop(1.0, D2D1_COLOR_F { r: 0.0, g: 0.0, b: 1.0, a: 1.0 })
+///     .direction(GradientDirection::from("to bottom"))
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GradientBuilder {
+    gradient_stops: Vec<D2D1_GRADIENT_STOP>,
+    direction: Option<GradientDirection>,
+    shape: GradientShape,
+    extend_mode: GradientExtendMode,
+    gamma: GradientGamma,
+}
+
+impl GradientBuilder {
+    /// Creates a new, empty `GradientBuilder`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a color stop at the given position.
+    ///
+    /// # Parameters
+    /// - `position`: The position of the stop, ranging from 0.0 to 1.0.
+    /// - `color`: The color of the stop.
+    pub fn add_stop(mut self, position: f32, color: D2D1_COLOR_F) -> Self {
+        self.gradient_stops
+            .push(D2D1_GRADIENT_STOP { position, color });
+        self
+    }
+
+    /// Sets the direction of the gradient.
+    ///
+    /// # Parameters
+    /// - `direction`: The `GradientDirection`, either as a string or as coordinates.
+    pub fn direction(mut self, direction: GradientDirection) -> Self {
+        self.direction = Some(direction);
+        self
+    }
+
+    /// Sets the shape of the gradient.
+    ///
+    /// # Parameters
+    /// - `shape`: The `GradientShape`, either linear or radial.
+    pub fn shape(mut self, shape: GradientShape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    /// Sets how the gradient extends beyond its start and end points.
+    ///
+    /// # Parameters
+    /// - `extend_mode`: The `GradientExtendMode`, clamping by default.
+    pub fn extend_mode(mut self, extend_mode: GradientExtendMode) -> Self {
+        self.extend_mode = extend_mode;
+        self
+    }
+
+    /// Sets the gamma space in which the colors are interpolated.
+    ///
+    /// # Parameters
+    /// - `gamma`: The `GradientGamma`, sRGB (gamma 2.2) by default.
+    pub fn gamma(mut self, gamma: GradientGamma) -> Self {
+        self.gamma = gamma;
+        self
+    }
+
+    /// Builds the `Gradient`.
+    ///
+    /// # Returns
+    /// A `Result` containing the `Gradient`, or an `ErrorKind::InvalidData` error if fewer than
+    /// two stops were added, a position lies outside 0.0 to 1.0, positions are not sorted, or the
+    /// direction is invalid.
+    pub fn build(self) -> Result<Gradient> {
+        if self.gradient_stops.len() < 2 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "A gradient requires at least two stops",
+            ));
+        }
+
+        if let Some(stop) = self
+            .gradient_stops
+            .iter()
+            .find(|stop| !(0.0..=1.0).contains(&stop.position))
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("Gradient stop position out of range: {}", stop.position),
+            ));
         }
+
+        if self
+            .gradient_stops
+            .windows(2)
+            .any(|pair| pair[0].position > pair[1].position)
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "Gradient stop positions must be sorted in ascending order",
+            ));
+        }
+
+        let direction = match self.direction {
+            Some(ref direction) => parse_gradient_direction(direction)?,
+            None => GradientCoordinates {
+                start: [0.0, 0.5],
+                end: [1.0, 0.5],
+            },
+        };
+
+        Ok(Gradient {
+            extend_mode: self.extend_mode,
+            gamma: self.gamma,
+            ..Gradient::new(direction, self.gradient_stops, self.shape)
+        })
     }
 }
 
 /// Enum representing different types of gradient directions.
 /// It can either be a string describing the direction (e.g., "to right") or explicit coordinates for the gradient direction.
-#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 #[serde(untagged)]
 pub enum GradientDirection {
     /// Direction is represented as a string (e.g., "to right").
     Direction(String),
+    /// Direction is represented as an angle in degrees (e.g., `90`), following the same
+    /// convention as the `90deg` string form.
+    Angle(f32),
     /// Direction is represented as coordinates (e.g., GradientCoordinates).
-    Coordinates(GradientCoordinates),
+    ///
+    /// Coordinates deserialize from either `{ "start": [x1, y1], "end": [x2, y2] }` or a flat
+    /// `[x1, y1, x2, y2]` array, with every value in the range 0.0 to 1.0. They always serialize
+    /// to the object form.
+    Coordinates(
+        #[serde(
+            serialize_with = "GradientCoordinatesDef::serialize",
+            deserialize_with = "deserialize_coordinates"
+        )]
+        GradientCoordinates,
+    ),
+}
+
+/// Mirrors `GradientCoordinates` so it can be (de)serialized as `{ "start": [x, y], "end": [x, y] }`.
+#[derive(Deserialize, Serialize)]
+#[serde(remote = "GradientCoordinates")]
+struct GradientCoordinatesDef {
+    start: [f32; 2],
+    end: [f32; 2],
+}
+
+/// The shapes gradient coordinates are accepted in when deserializing.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum GradientCoordinatesRepr {
+    Object(#[serde(with = "GradientCoordinatesDef")] GradientCoordinates),
+    Array([f32; 4]),
+}
+
+/// Deserializes `GradientCoordinates` from either the object or the flat array form, rejecting
+/// values outside 0.0 to 1.0.
+fn deserialize_coordinates<'de, D>(
+    deserializer: D,
+) -> core::result::Result<GradientCoordinates, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let coordinates = match GradientCoordinatesRepr::deserialize(deserializer)? {
+        GradientCoordinatesRepr::Object(coordinates) => coordinates,
+        GradientCoordinatesRepr::Array([x1, y1, x2, y2]) => GradientCoordinates {
+            start: [x1, y1],
+            end: [x2, y2],
+        },
+    };
+
+    validate_coordinates(&coordinates).map_err(|e| DeError::custom(e.message()))?;
+
+    Ok(coordinates)
+}
+
+/// Rounds every value of the coordinates to four decimal places.
+///
+/// Rounding hides floating point noise, such as between `90deg` and `to right`, so equivalent
+/// directions compare equal.
+pub(crate) fn round_coordinates(coordinates: &GradientCoordinates) -> GradientCoordinates {
+    let round = |point: [f32; 2]| point.map(|value| (value * 10_000.0).round() / 10_000.0);
+
+    GradientCoordinates {
+        start: round(coordinates.start),
+        end: round(coordinates.end),
+    }
+}
+
+/// Checks whether stop positions are the evenly spaced positions used for a `ColorMapping`
+/// without `positions`.
+pub(crate) fn is_evenly_spaced(positions: &[f32]) -> bool {
+    let last = positions.len().saturating_sub(1).max(1) as f32;
+
+    positions
+        .iter()
+        .enumerate()
+        .all(|(i, position)| (position - i as f32 / last).abs() <= 1e-6)
+}
+
+/// Checks that every value of the coordinates lies within 0.0 to 1.0.
+///
+/// # Returns
+/// An `ErrorKind::InvalidGradientCoordinates` error naming the first value out of range.
+pub(crate) fn validate_coordinates(coordinates: &GradientCoordinates) -> Result<()> {
+    let mut values = coordinates.start.iter().chain(coordinates.end.iter());
+
+    match values.find(|v| !(0.0..=1.0).contains(*v)) {
+        Some(value) => Err(Error::new(
+            ErrorKind::InvalidGradientCoordinates,
+            format!(
+                "gradient coordinate {} is outside the range 0.0 to 1.0",
+                value
+            ),
+        )),
+        None => Ok(()),
+    }
 }
 
 impl From<&str> for GradientDirection {
@@ -106,24 +1118,932 @@ impl From<&str> for GradientDirection {
     }
 }
 
+/// The interpolation used between adjacent color stops of a `ColorMapping`.
+///
+/// Direct2D always interpolates linearly between stops, so non-linear modes are approximated by
+/// synthesizing intermediate stops between each pair of colors.
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum GradientInterpolation {
+    /// Colors blend linearly between stops.
+    #[default]
+    Linear,
+    /// Colors ease in and out of each stop. Each pair of stops is split into
+    /// `EASE_IN_OUT_SEGMENTS` segments, inserting 7 synthetic stops between them.
+    EaseInOut,
+    /// Colors change in the given number of hard steps between each pair of stops. Each step
+    /// boundary inserts two synthetic stops at the same position. At most `MAX_GRADIENT_STEPS`
+    /// steps are allowed.
+    Steps(u32),
+}
+
+impl GradientInterpolation {
+    fn is_linear(&self) -> bool {
+        *self == Self::Linear
+    }
+
+    /// Checks that a `Steps` count is at most `MAX_GRADIENT_STEPS`, so a config value can't
+    /// expand into an unbounded number of stops.
+    pub(crate) fn validate(&self) -> Result<()> {
+        match *self {
+            Self::Steps(steps) if steps > MAX_GRADIENT_STEPS => Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "Gradient step count {} exceeds the maximum of {}",
+                    steps, MAX_GRADIENT_STEPS
+                ),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The number of segments each pair of stops is split into for `GradientInterpolation::EaseInOut`.
+pub const EASE_IN_OUT_SEGMENTS: u32 = 8;
+
+/// The largest step count accepted for `GradientInterpolation::Steps`.
+pub const MAX_GRADIENT_STEPS: u32 = 256;
+
 /// A structure that defines a gradient mapping, which contains a list of color stops and a direction.
-#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 pub struct ColorMapping {
     /// A list of colors in the gradient, represented as hexadecimal color strings.
     pub colors: Vec<String>,
+    /// An optional list of colors used instead of `colors` when the color is inactive.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inactive_colors: Option<Vec<String>>,
+    /// An optional list of stop positions, one per color, ranging from 0.0 to 1.0.
+    /// When absent, the colors are spaced evenly across the gradient. The positions don't need to
+    /// be in order, and a color may repeat at several positions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub positions: Option<Vec<f32>>,
+    /// The interpolation used between adjacent colors, defaulting to linear.
+    #[serde(default, skip_serializing_if = "GradientInterpolation::is_linear")]
+    pub interpolation: GradientInterpolation,
     /// The direction of the gradient, represented as a `GradientDirection`.
     pub direction: GradientDirection,
+    /// An optional opacity applied to the brush of the resulting color, clamped to 0.0 to 1.0.
+    /// When absent, the brush is fully opaque.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub opacity: Option<f32>,
 }
 
 pub trait ColorMappingImpl {
     fn new(colors: &[&str], direction: GradientDirection) -> Self;
+
+    /// Creates a `ColorMapping` running between explicit normalized coordinates.
+    ///
+    /// # Parameters
+    /// - `colors`: The colors of the mapping.
+    /// - `start`: The normalized `[x, y]` point where the gradient starts.
+    /// - `end`: The normalized `[x, y]` point where the gradient ends.
+    ///
+    /// # Returns
+    /// A `Result` containing the `ColorMapping`, or an `ErrorKind::InvalidGradientCoordinates`
+    /// error if any coordinate lies outside 0.0 to 1.0.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use win_color::{ColorMapping, ColorMappingImpl};
+    /// # fn main() -> win_color::Result<()> {
+    /// let mapping = ColorMapping::with_coordinates(&["#ff0000", "#0000ff"], [0.0, 0.0], [1.0, 1.0])?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn with_coordinates(colors: &[&str], start: [f32; 2], end: [f32; 2]) -> Result<Self>
+    where
+        Self: Sized;
+
+    /// Checks the parts of the mapping that can be validated without resolving its colors.
+    ///
+    /// Call this after loading a config to reject invalid mappings early, rather than when the
+    /// mapping is first converted into a `Color`.
+    ///
+    /// # Returns
+    /// `Ok(())` if the mapping is valid, an `ErrorKind::InvalidData` error if `colors` is empty,
+    /// `positions` does not match it or the step count exceeds `MAX_GRADIENT_STEPS`, or an
+    /// `ErrorKind::InvalidGradientCoordinates` error if the direction is invalid.
+    fn validate(&self) -> Result<()>;
+
+    /// Converts the mapping into a canonical form, so equivalent mappings compare equal.
+    ///
+    /// The direction is resolved to `GradientDirection::Coordinates`, rounded to four decimal
+    /// places, and every color is resolved and formatted as a lowercase `#rrggbbaa` hex string.
+    /// Keywords such as `accent` are resolved to their current value, with `inactive_colors`
+    /// resolved as inactive. Evenly spaced `positions` are dropped, and the opacity is clamped as
+    /// when parsing, and dropped when it is fully opaque or `NaN`. Canonicalizing is idempotent,
+    /// and for a gradient mapping with linear interpolation and no `inactive_colors`, the result
+    /// matches `ColorImpl::to_global_color` of the parsed color.
+    ///
+    /// # Returns
+    /// A `Result` containing the canonical `ColorMapping`, or an error if a color or the direction
+    /// is invalid. Gradients used as colors fail with an `ErrorKind::InvalidInput` error.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use win_color::{ColorMapping, ColorMappingImpl, GradientDirection};
+    /// # fn main() -> win_color::Result<()> {
+    /// let a = ColorMapping::new(&["#f00", "#00F"], GradientDirection::from("90deg"));
+    /// let b = ColorMapping::new(&["#ff0000", "rgb(0, 0, 255)"], GradientDirection::from("to right"));
+    /// assert_eq!(a.canonicalize()?, b.canonicalize()?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn canonicalize(&self) -> Result<ColorMapping>;
 }
 
 impl ColorMappingImpl for ColorMapping {
     fn new(colors: &[&str], direction: GradientDirection) -> Self {
         Self {
             colors: colors.iter().map(|&s| s.to_string()).collect(),
+            inactive_colors: None,
+            positions: None,
+            interpolation: GradientInterpolation::Linear,
             direction,
+            opacity: None,
         }
     }
+
+    fn with_coordinates(colors: &[&str], start: [f32; 2], end: [f32; 2]) -> Result<Self> {
+        let coordinates = GradientCoordinates { start, end };
+        validate_coordinates(&coordinates)?;
+
+        Ok(Self::new(
+            colors,
+            GradientDirection::Coordinates(coordinates),
+        ))
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.colors.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "A color mapping requires at least one color",
+            ));
+        }
+
+        if let Some(ref positions) = self.positions {
+            if positions.len() != self.colors.len() {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "Expected {} gradient positions but found {}",
+                        self.colors.len(),
+                        positions.len()
+                    ),
+                ));
+            }
+
+            if let Some(position) = positions.iter().find(|p| !(0.0..=1.0).contains(*p)) {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!("Gradient stop position out of range: {}", position),
+                ));
+            }
+        }
+
+        self.interpolation.validate()?;
+
+        parse_gradient_direction(&self.direction).map(|_| ())
+    }
+
+    fn canonicalize(&self) -> Result<ColorMapping> {
+        let canonical_colors = |colors: &[String], is_active: bool| {
+            colors
+                .iter()
+                .map(|color| match parse_color_string(color, Some(is_active))? {
+                    Color::Solid(solid) => Ok(to_hex_rgba(&solid.effective_color())),
+                    Color::Gradient(_) => Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("Expected a solid color but found a gradient: {}", color),
+                    )),
+                })
+                .collect::<Result<Vec<_>>>()
+        };
+
+        let coordinates = parse_gradient_direction(&self.direction)?;
+
+        Ok(ColorMapping {
+            colors: canonical_colors(&self.colors, true)?,
+            inactive_colors: self
+                .inactive_colors
+                .as_deref()
+                .map(|colors| canonical_colors(colors, false))
+                .transpose()?,
+            positions: self
+                .positions
+                .clone()
+                .filter(|positions| !is_evenly_spaced(positions)),
+            interpolation: self.interpolation,
+            direction: GradientDirection::Coordinates(round_coordinates(&coordinates)),
+            opacity: self
+                .opacity
+                .filter(|opacity| !opacity.is_nan())
+                .map(|opacity| opacity.clamp(0.0, 1.0))
+                .filter(|opacity| *opacity != 1.0),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: D2D1_COLOR_F = D2D1_COLOR_F {
+        r: 1.0,
+        g: 0.0,
+        b: 0.0,
+        a: 1.0,
+    };
+    const BLUE: D2D1_COLOR_F = D2D1_COLOR_F {
+        r: 0.0,
+        g: 0.0,
+        b: 1.0,
+        a: 1.0,
+    };
+
+    fn stop(position: f32, color: D2D1_COLOR_F) -> D2D1_GRADIENT_STOP {
+        D2D1_GRADIENT_STOP { position, color }
+    }
+
+    fn two_stop_gradient(shape: GradientShape) -> Gradient {
+        let direction = GradientCoordinates {
+            start: [0.5, 0.5],
+            end: [1.0, 1.0],
+        };
+
+        Gradient::new(direction, vec![stop(0.0, RED), stop(0.75, BLUE)], shape)
+    }
+
+    #[test]
+    fn reverse_swaps_the_colors_of_a_two_stop_gradient() {
+        let mut gradient = two_stop_gradient(GradientShape::Linear);
+        let direction = gradient.direction.clone();
+        gradient.reverse();
+
+        assert_eq!(gradient.direction, direction);
+        assert_eq!(gradient.gradient_stops[0].position, 0.25);
+        assert_eq!(gradient.gradient_stops[0].color, BLUE);
+        assert_eq!(gradient.gradient_stops[1].position, 1.0);
+        assert_eq!(gradient.gradient_stops[1].color, RED);
+        assert_eq!(gradient.color_at(0.0), BLUE);
+        assert_eq!(gradient.color_at(1.0), RED);
+    }
+
+    #[test]
+    fn reverse_keeps_the_radial_center() {
+        let mut gradient = two_stop_gradient(GradientShape::Radial);
+        gradient.reverse();
+
+        assert_eq!(gradient.direction.start, [0.5, 0.5]);
+        assert_eq!(gradient.direction.end, [1.0, 1.0]);
+        assert_eq!(gradient.color_at(0.0), BLUE);
+        assert_eq!(gradient.color_at(1.0), RED);
+    }
+
+    #[test]
+    fn reversing_twice_restores_the_gradient() {
+        let gradient = two_stop_gradient(GradientShape::Conic);
+        let mut reversed = gradient.clone();
+        reversed.reverse();
+        reversed.reverse();
+
+        assert_eq!(reversed, gradient);
+    }
+
+    #[test]
+    fn color_at_clamps_to_the_end_stops() {
+        let gradient = two_stop_gradient(GradientShape::Linear);
+
+        assert_eq!(gradient.color_at(-1.0), RED);
+        assert_eq!(gradient.color_at(0.0), RED);
+        assert_eq!(gradient.color_at(0.75), BLUE);
+        assert_eq!(gradient.color_at(2.0), BLUE);
+    }
+
+    #[test]
+    fn color_at_interpolates_between_stops() {
+        let gradient = two_stop_gradient(GradientShape::Linear);
+        let color = gradient.color_at(0.375);
+
+        assert!((color.r - 0.5).abs() < 1e-6);
+        assert_eq!(color.g, 0.0);
+        assert!((color.b - 0.5).abs() < 1e-6);
+        assert_eq!(color.a, 1.0);
+    }
+
+    #[test]
+    fn color_at_handles_single_and_empty_stops() {
+        let direction = GradientCoordinates {
+            start: [0.0, 0.0],
+            end: [1.0, 0.0],
+        };
+        let single = Gradient::new(
+            direction.clone(),
+            vec![D2D1_GRADIENT_STOP {
+                position: 0.5,
+                color: RED,
+            }],
+            GradientShape::Linear,
+        );
+        let empty = Gradient::new(direction, Vec::new(), GradientShape::Linear);
+
+        assert_eq!(single.color_at(0.0), RED);
+        assert_eq!(single.color_at(1.0), RED);
+        assert_eq!(empty.color_at(0.5), D2D1_COLOR_F::default());
+    }
+
+    #[test]
+    fn stops_yields_positions_and_solids() {
+        let gradient = Gradient::new(
+            GradientCoordinates {
+                start: [0.0, 0.0],
+                end: [1.0, 0.0],
+            },
+            vec![stop(0.0, RED), stop(0.4, BLUE), stop(1.0, RED)],
+            GradientShape::Linear,
+        );
+        let stops: Vec<_> = gradient.stops().collect();
+
+        assert_eq!(stops.len(), 3);
+        assert_eq!(
+            stops
+                .iter()
+                .map(|(position, _)| *position)
+                .collect::<Vec<_>>(),
+            [0.0, 0.4, 1.0]
+        );
+        assert_eq!(stops[1].1.color, BLUE);
+        assert!(stops.iter().all(|(_, solid)| solid.brush.is_none()));
+    }
+
+    #[test]
+    fn normalizes_unsorted_out_of_range_stops() {
+        let gradient = Gradient::new(
+            GradientCoordinates {
+                start: [0.0, 0.0],
+                end: [1.0, 0.0],
+            },
+            vec![
+                stop(1.5, RED),
+                stop(0.5, BLUE),
+                stop(-0.25, BLUE),
+                stop(0.5, RED),
+            ],
+            GradientShape::Linear,
+        );
+        let stops: Vec<_> = gradient
+            .gradient_stops
+            .iter()
+            .map(|stop| (stop.position, stop.color))
+            .collect();
+
+        assert_eq!(stops, [(0.0, BLUE), (0.5, BLUE), (0.5, RED), (1.0, RED)]);
+    }
+
+    #[test]
+    fn pixel_points_scale_with_the_window() {
+        let gradient = two_stop_gradient(GradientShape::Linear);
+
+        for (right, bottom) in [(200, 100), (400, 300)] {
+            let window_rect = RECT {
+                left: 0,
+                top: 0,
+                right,
+                bottom,
+            };
+            let (start, end) = gradient.pixel_points(&window_rect);
+            let (width, height) = (right as f32, bottom as f32);
+
+            assert_eq!(
+                start,
+                D2D_POINT_2F {
+                    x: width * 0.5,
+                    y: height * 0.5
+                }
+            );
+            assert_eq!(
+                end,
+                D2D_POINT_2F {
+                    x: width,
+                    y: height
+                }
+            );
+        }
+    }
+
+    fn quarter_gradient(shape: GradientShape, extend_mode: GradientExtendMode) -> Gradient {
+        let mut gradient = Gradient::new(
+            GradientCoordinates {
+                start: [0.0, 0.5],
+                end: [1.0, 0.5],
+            },
+            vec![stop(0.25, RED), stop(0.5, BLUE)],
+            shape,
+        );
+        gradient.extend_mode = extend_mode;
+        gradient
+    }
+
+    #[test]
+    fn repeating_gradients_repeat_between_their_stops() {
+        let window_rect = RECT {
+            left: 0,
+            top: 0,
+            right: 200,
+            bottom: 100,
+        };
+
+        for (shape, extend_mode, (from, to), (start_x, end_x)) in [
+            (
+                GradientShape::Linear,
+                GradientExtendMode::Clamp,
+                (0.0, 1.0),
+                (0.0, 200.0),
+            ),
+            (
+                GradientShape::Linear,
+                GradientExtendMode::Wrap,
+                (0.25, 0.5),
+                (50.0, 100.0),
+            ),
+            (
+                GradientShape::Linear,
+                GradientExtendMode::Mirror,
+                (0.25, 0.5),
+                (50.0, 100.0),
+            ),
+            (
+                GradientShape::Radial,
+                GradientExtendMode::Wrap,
+                (0.0, 0.5),
+                (0.0, 100.0),
+            ),
+        ] {
+            let gradient = quarter_gradient(shape, extend_mode);
+            let (start, end) = gradient.pixel_points(&window_rect);
+            let positions: Vec<f32> = gradient
+                .brush_stops()
+                .iter()
+                .map(|stop| stop.position)
+                .collect();
+            let expected: Vec<f32> = [0.25, 0.5]
+                .iter()
+                .map(|position| (position - from) / (to - from))
+                .collect();
+
+            assert_eq!(gradient.repeat_span(), (from, to), "{:?}", extend_mode);
+            assert_eq!((start.x, end.x), (start_x, end_x), "{:?}", extend_mode);
+            assert_eq!(positions, expected, "{:?}", extend_mode);
+        }
+    }
+
+    #[test]
+    fn color_at_repeats_between_the_stops() {
+        let wrap = quarter_gradient(GradientShape::Linear, GradientExtendMode::Wrap);
+        let mirror = quarter_gradient(GradientShape::Linear, GradientExtendMode::Mirror);
+
+        assert_eq!(wrap.color_at(0.5), BLUE);
+        assert_eq!(wrap.color_at(0.75), RED);
+        assert_eq!(mirror.color_at(0.5), BLUE);
+        assert_eq!(mirror.color_at(0.75), RED);
+        assert_eq!(mirror.color_at(1.0), BLUE);
+    }
+
+    #[test]
+    fn blends_stops_and_direction() {
+        let from = two_stop_gradient(GradientShape::Linear);
+        let to = Gradient::new(
+            GradientCoordinates {
+                start: [0.0, 0.0],
+                end: [1.0, 0.0],
+            },
+            vec![stop(0.0, BLUE), stop(1.0, RED)],
+            GradientShape::Linear,
+        );
+        let blended = from.blend(&to, 0.25).unwrap();
+
+        assert_eq!(blended.direction.start, [0.375, 0.375]);
+        assert_eq!(blended.direction.end, [1.0, 0.75]);
+        assert_eq!(blended.gradient_stops[0].position, 0.0);
+        assert_eq!(blended.gradient_stops[1].position, 0.8125);
+        for (stop, (r, b)) in blended
+            .gradient_stops
+            .iter()
+            .zip([(0.75, 0.25), (0.25, 0.75)])
+        {
+            assert!((stop.color.r - r).abs() < 1e-6, "{:?}", stop.color);
+            assert!((stop.color.b - b).abs() < 1e-6, "{:?}", stop.color);
+        }
+    }
+
+    #[test]
+    fn blending_mismatched_stop_counts_is_an_error() {
+        let from = two_stop_gradient(GradientShape::Linear);
+        let mut to = from.clone();
+        to.gradient_stops.pop();
+
+        assert_eq!(
+            from.blend(&to, 0.5).unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+    }
+
+    #[test]
+    fn average_color_weights_stops_by_span() {
+        let direction = GradientCoordinates {
+            start: [0.0, 0.0],
+            end: [1.0, 0.0],
+        };
+        let symmetric = Gradient::new(
+            direction.clone(),
+            vec![stop(0.0, RED), stop(1.0, BLUE)],
+            GradientShape::Linear,
+        );
+        let weighted = Gradient::new(
+            direction.clone(),
+            vec![stop(0.0, RED), stop(0.6, RED), stop(1.0, BLUE)],
+            GradientShape::Linear,
+        );
+        let single = Gradient::new(direction, vec![stop(0.3, BLUE)], GradientShape::Linear);
+
+        for (gradient, (r, b)) in [
+            (symmetric, (0.5, 0.5)),
+            (weighted, (0.8, 0.2)),
+            (two_stop_gradient(GradientShape::Linear), (0.375, 0.625)),
+        ] {
+            let average = gradient.average_color();
+
+            assert!((average.r - r).abs() < 1e-5, "{:?}", average);
+            assert!((average.b - b).abs() < 1e-5, "{:?}", average);
+            assert_eq!(average.a, 1.0);
+        }
+        assert_eq!(single.average_color(), BLUE);
+    }
+
+    #[test]
+    fn builder_selects_gamma() {
+        let default = GradientBuilder::new()
+            .add_stop(0.0, RED)
+            .add_stop(1.0, BLUE)
+            .build()
+            .unwrap();
+        assert_eq!(default.gamma, GradientGamma::Srgb);
+
+        for (gamma, expected) in [
+            (GradientGamma::Srgb, D2D1_GAMMA_2_2),
+            (GradientGamma::Linear, D2D1_GAMMA_1_0),
+        ] {
+            let gradient = GradientBuilder::new()
+                .add_stop(0.0, RED)
+                .add_stop(1.0, BLUE)
+                .gamma(gamma)
+                .build()
+                .unwrap();
+
+            assert_eq!(gradient.gamma, gamma);
+            assert_eq!(D2D1_GAMMA::from(gradient.gamma), expected);
+        }
+    }
+
+    #[test]
+    fn set_stops_sorts_the_new_stops() {
+        let mut gradient = two_stop_gradient(GradientShape::Linear);
+        gradient.brush_key = Some(1);
+        gradient
+            .set_stops(vec![(1.0, RED), (0.0, BLUE), (0.5, RED)])
+            .unwrap();
+        let stops: Vec<_> = gradient
+            .gradient_stops
+            .iter()
+            .map(|stop| (stop.position, stop.color))
+            .collect();
+
+        assert_eq!(stops, [(0.0, BLUE), (0.5, RED), (1.0, RED)]);
+        assert!(gradient.brush.is_none());
+        assert_eq!(gradient.brush_key, None);
+    }
+
+    #[test]
+    fn set_stops_rejects_invalid_stops() {
+        let original = two_stop_gradient(GradientShape::Linear);
+
+        for stops in [
+            vec![(0.0, RED), (1.5, BLUE)],
+            vec![(-0.1, RED), (1.0, BLUE)],
+            vec![(0.5, RED)],
+        ] {
+            let mut gradient = original.clone();
+            let error = gradient.set_stops(stops).unwrap_err();
+
+            assert_eq!(error.kind(), ErrorKind::InvalidData);
+            assert_eq!(gradient, original);
+        }
+    }
+
+    #[test]
+    fn remaps_positions_into_a_sub_range() {
+        let mut gradient = Gradient::new(
+            GradientCoordinates {
+                start: [0.0, 0.0],
+                end: [1.0, 0.0],
+            },
+            vec![stop(0.0, RED), stop(1.0, BLUE)],
+            GradientShape::Linear,
+        );
+        gradient.remap_positions(0.25, 0.75).unwrap();
+
+        assert_eq!(gradient.gradient_stops[0].position, 0.25);
+        assert_eq!(gradient.gradient_stops[1].position, 0.75);
+        assert_eq!(gradient.color_at(0.1), RED);
+        assert_eq!(gradient.color_at(0.9), BLUE);
+    }
+
+    #[test]
+    fn remap_positions_rejects_invalid_ranges() {
+        let original = two_stop_gradient(GradientShape::Linear);
+
+        for (start, end) in [(0.75, 0.25), (0.5, 0.5), (-0.1, 0.5), (0.5, 1.1)] {
+            let mut gradient = original.clone();
+            let error = gradient.remap_positions(start, end).unwrap_err();
+
+            assert_eq!(error.kind(), ErrorKind::InvalidData);
+            assert_eq!(gradient, original);
+        }
+    }
+
+    #[test]
+    fn recovers_css_angles_from_coordinates() {
+        for (start, end, angle) in [
+            ([0.5, 1.0], [0.5, 0.0], 0.0),
+            ([0.0, 0.5], [1.0, 0.5], 90.0),
+            ([0.5, 0.0], [0.5, 1.0], 180.0),
+            ([1.0, 0.5], [0.0, 0.5], 270.0),
+            ([0.0, 1.0], [1.0, 0.0], 45.0),
+            ([0.5, 0.5], [0.5, 0.5], 0.0),
+        ] {
+            let coordinates = GradientCoordinates { start, end };
+
+            assert!(
+                (coordinates.angle_degrees() - angle).abs() < 1e-4,
+                "{:?} gave {}",
+                coordinates,
+                coordinates.angle_degrees()
+            );
+        }
+    }
+
+    #[test]
+    fn quantizes_to_evenly_spaced_samples() {
+        let stops = (0..10)
+            .map(|i| {
+                let t = i as f32 / 9.0;
+                D2D1_GRADIENT_STOP {
+                    position: t,
+                    color: D2D1_COLOR_F { r: t, ..BLUE },
+                }
+            })
+            .collect();
+        let mut gradient = Gradient::new(
+            GradientCoordinates {
+                start: [0.0, 0.0],
+                end: [1.0, 0.0],
+            },
+            stops,
+            GradientShape::Linear,
+        );
+        gradient.quantize(3).unwrap();
+
+        assert_eq!(gradient.gradient_stops.len(), 3);
+        for (stop, t) in gradient.gradient_stops.iter().zip([0.0, 0.5, 1.0]) {
+            assert_eq!(stop.position, t);
+            assert!((stop.color.r - t).abs() < 1e-6, "{:?}", stop);
+        }
+    }
+
+    #[test]
+    fn quantize_leaves_small_gradients_and_rejects_tiny_caps() {
+        let original = two_stop_gradient(GradientShape::Linear);
+        let mut gradient = original.clone();
+
+        gradient.quantize(2).unwrap();
+        assert_eq!(gradient, original);
+        assert_eq!(
+            gradient.quantize(1).unwrap_err().kind(),
+            ErrorKind::InvalidInput
+        );
+    }
+
+    #[test]
+    fn transparent_stops_are_visually_equal() {
+        let mut a = two_stop_gradient(GradientShape::Linear);
+        let mut b = a.clone();
+        a.gradient_stops[0].color = D2D1_COLOR_F { a: 0.0, ..RED };
+        b.gradient_stops[0].color = D2D1_COLOR_F { a: 0.0, ..BLUE };
+        b.direction.end[0] -= 1e-4;
+
+        assert_ne!(a, b);
+        assert!(a.visually_eq(&b));
+    }
+
+    #[test]
+    fn visible_differences_are_not_visually_equal() {
+        let a = two_stop_gradient(GradientShape::Linear);
+        let mut b = a.clone();
+        b.gradient_stops[0].color = D2D1_COLOR_F { a: 0.5, ..RED };
+        let mut c = a.clone();
+        c.direction.end[0] = 0.9;
+
+        assert!(!a.visually_eq(&b));
+        assert!(!a.visually_eq(&c));
+        assert!(!a.visually_eq(&two_stop_gradient(GradientShape::Radial)));
+    }
+
+    #[test]
+    fn deserializes_coordinates_from_object_and_array() {
+        let expected = GradientDirection::Coordinates(GradientCoordinates {
+            start: [0.0, 0.25],
+            end: [1.0, 0.75],
+        });
+
+        for json in [
+            r#"{ "start": [0.0, 0.25], "end": [1.0, 0.75] }"#,
+            "[0.0, 0.25, 1.0, 0.75]",
+        ] {
+            let direction: GradientDirection = serde_json::from_str(json).unwrap();
+            assert_eq!(direction, expected);
+        }
+    }
+
+    #[test]
+    fn deserializes_numeric_angles() {
+        let direction: GradientDirection = serde_json::from_str("90").unwrap();
+        assert_eq!(direction, GradientDirection::Angle(90.0));
+        assert_eq!(
+            parse_gradient_direction(&direction).unwrap(),
+            parse_gradient_direction(&GradientDirection::from("90deg")).unwrap()
+        );
+
+        let mapping: ColorMapping =
+            serde_json::from_str(r##"{ "colors": ["#ff0000", "#0000ff"], "direction": 45.5 }"##)
+                .unwrap();
+        assert_eq!(mapping.direction, GradientDirection::Angle(45.5));
+        assert_eq!(
+            serde_json::from_str::<GradientDirection>(r#""to right""#).unwrap(),
+            GradientDirection::from("to right")
+        );
+    }
+
+    #[test]
+    fn rejects_out_of_range_coordinates() {
+        for json in [
+            r#"{ "start": [0.0, 0.0], "end": [1.5, 1.0] }"#,
+            "[-0.5, 0.0, 1.0, 1.0]",
+        ] {
+            assert!(serde_json::from_str::<GradientDirection>(json).is_err());
+        }
+    }
+
+    #[test]
+    fn validates_a_valid_mapping() {
+        let mapping =
+            ColorMapping::new(&["#ff0000", "#0000ff"], GradientDirection::from("to right"));
+
+        assert!(mapping.validate().is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_bogus_mappings() {
+        let bogus_direction =
+            ColorMapping::new(&["#ff0000", "#0000ff"], GradientDirection::from("nonsense"));
+        let no_colors = ColorMapping::new(&[], GradientDirection::from("to right"));
+
+        assert_eq!(
+            bogus_direction.validate().unwrap_err().kind(),
+            ErrorKind::InvalidGradientCoordinates
+        );
+        assert_eq!(
+            no_colors.validate().unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+    }
+
+    #[test]
+    fn equivalent_mappings_canonicalize_equal() {
+        let spelled = ColorMapping::new(
+            &["#F00", "rgba(0, 0, 255, 1)"],
+            GradientDirection::from("90deg"),
+        );
+        let coordinates =
+            ColorMapping::with_coordinates(&["#ff0000ff", "#0000FF"], [0.0, 0.5], [1.0, 0.5])
+                .unwrap();
+        let canonical = spelled.canonicalize().unwrap();
+
+        assert_ne!(spelled, coordinates);
+        assert_eq!(canonical, coordinates.canonicalize().unwrap());
+        assert_eq!(canonical.colors, ["#ff0000ff", "#0000ffff"]);
+        assert_eq!(canonical.canonicalize().unwrap(), canonical);
+    }
+
+    #[test]
+    fn different_mappings_canonicalize_differently() {
+        let a = ColorMapping::new(&["#ff0000", "#0000ff"], GradientDirection::from("90deg"));
+        let b = ColorMapping::new(
+            &["#ff0000", "#0000ff"],
+            GradientDirection::from("to bottom"),
+        );
+        let gradient = ColorMapping::new(
+            &["linear-gradient(90deg, #ff0000, #0000ff)"],
+            GradientDirection::from("90deg"),
+        );
+
+        assert_ne!(a.canonicalize().unwrap(), b.canonicalize().unwrap());
+        assert_eq!(
+            gradient.canonicalize().unwrap_err().kind(),
+            ErrorKind::InvalidInput
+        );
+    }
+
+    #[test]
+    fn dedup_stops_collapses_identical_neighbours() {
+        let mut gradient = two_stop_gradient(GradientShape::Linear);
+        gradient.gradient_stops = vec![
+            stop(0.0, RED),
+            stop(0.5, BLUE),
+            stop(0.5, BLUE),
+            stop(0.50005, BLUE),
+            stop(1.0, RED),
+        ];
+        gradient.dedup_stops();
+
+        assert_eq!(
+            gradient.gradient_stops,
+            vec![stop(0.0, RED), stop(0.5, BLUE), stop(1.0, RED)]
+        );
+    }
+
+    #[test]
+    fn dedup_stops_keeps_two_stops_and_distinct_neighbours() {
+        let mut gradient = two_stop_gradient(GradientShape::Linear);
+        gradient.gradient_stops = vec![stop(0.5, RED), stop(0.5, RED), stop(0.5, RED)];
+        gradient.dedup_stops();
+
+        assert_eq!(
+            gradient.gradient_stops,
+            vec![stop(0.5, RED), stop(0.5, RED)]
+        );
+
+        let distinct = vec![stop(0.0, RED), stop(0.0, BLUE), stop(0.5, BLUE)];
+        gradient.gradient_stops = distinct.clone();
+        gradient.dedup_stops();
+
+        assert_eq!(gradient.gradient_stops, distinct);
+    }
+
+    #[test]
+    fn from_pixels_normalizes_to_the_window() {
+        let rect = RECT {
+            left: 0,
+            top: 0,
+            right: 200,
+            bottom: 100,
+        };
+        let coordinates = GradientCoordinates::from_pixels([50.0, 25.0], [150.0, 100.0], &rect);
+
+        assert_eq!(coordinates.start, [0.25, 0.25]);
+        assert_eq!(coordinates.end, [0.75, 1.0]);
+
+        let clamped = GradientCoordinates::from_pixels([-20.0, 50.0], [400.0, 50.0], &rect);
+
+        assert_eq!(clamped.start, [0.0, 0.5]);
+        assert_eq!(clamped.end, [1.0, 0.5]);
+    }
+
+    #[test]
+    fn from_pixels_handles_empty_windows() {
+        let rect = RECT {
+            left: 10,
+            top: 10,
+            right: 10,
+            bottom: 10,
+        };
+        let coordinates = GradientCoordinates::from_pixels([5.0, 5.0], [20.0, 20.0], &rect);
+
+        assert_eq!(coordinates.start, [0.0, 0.0]);
+        assert_eq!(coordinates.end, [0.0, 0.0]);
+    }
+
+    #[test]
+    fn is_effectively_solid_tolerates_rounding() {
+        let nearly_red = D2D1_COLOR_F { g: 0.001, ..RED };
+        let mut gradient = two_stop_gradient(GradientShape::Linear);
+
+        assert!(!gradient.is_effectively_solid());
+
+        gradient.gradient_stops = vec![stop(0.0, RED), stop(0.5, nearly_red), stop(1.0, RED)];
+        assert!(gradient.is_effectively_solid());
+
+        gradient.gradient_stops[1].color.g = 0.01;
+        assert!(!gradient.is_effectively_solid());
+    }
 }
@@ -10,23 +10,50 @@
 //! - Methods for converting these color types into Direct2D brushes for rendering, as well as handling opacity and transformations.
 mod error;
 mod gradient;
+mod named;
+mod palette;
 mod parser;
 mod solid;
+mod utils;
 
+use gradient::direction_angle;
+use gradient::format_direction;
+use gradient::is_evenly_spaced;
+use gradient::round_coordinates;
 use parser::parse_color_mapping;
 use parser::parse_color_string;
+use serde::de::Error as DeError;
 use serde::Deserialize;
-use windows::core::Result as WinResult;
+use serde::Deserializer;
+use serde::Serialize;
+use std::hash::DefaultHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::sync::OnceLock;
+use std::sync::RwLock;
+use utils::darken;
+use utils::darken_linear;
+use utils::lerp;
+use utils::lerp_color;
+use utils::lighten;
+use utils::lighten_linear;
+use utils::rotate_hue;
+use utils::to_hex;
+use utils::to_hex_rgba;
+use utils::validate_color;
+use windows::core::IUnknown;
+use windows::core::Interface;
 use windows::Foundation::Numerics::Matrix3x2;
 use windows::Win32::Foundation::RECT;
 use windows::Win32::Graphics::Direct2D::Common::D2D1_COLOR_F;
+use windows::Win32::Graphics::Direct2D::Common::D2D1_GRADIENT_STOP;
 use windows::Win32::Graphics::Direct2D::Common::D2D_POINT_2F;
 use windows::Win32::Graphics::Direct2D::ID2D1Brush;
-use windows::Win32::Graphics::Direct2D::ID2D1HwndRenderTarget;
+use windows::Win32::Graphics::Direct2D::ID2D1RenderTarget;
+use windows::Win32::Graphics::Direct2D::ID2D1SolidColorBrush;
 use windows::Win32::Graphics::Direct2D::D2D1_BRUSH_PROPERTIES;
-use windows::Win32::Graphics::Direct2D::D2D1_EXTEND_MODE_CLAMP;
-use windows::Win32::Graphics::Direct2D::D2D1_GAMMA_2_2;
 use windows::Win32::Graphics::Direct2D::D2D1_LINEAR_GRADIENT_BRUSH_PROPERTIES;
+use windows::Win32::Graphics::Direct2D::D2D1_RADIAL_GRADIENT_BRUSH_PROPERTIES;
 
 pub use colorparser_css::GradientCoordinates;
 pub use error::Error;
@@ -35,12 +62,39 @@ pub use error::Result;
 pub use gradient::ColorMapping;
 pub use gradient::ColorMappingImpl;
 pub use gradient::Gradient;
+pub use gradient::GradientBuilder;
+pub use gradient::GradientCoordinatesImpl;
 pub use gradient::GradientDirection;
+pub use gradient::GradientExtendMode;
+pub use gradient::GradientGamma;
 pub use gradient::GradientImpl;
+pub use gradient::GradientInterpolation;
+pub use gradient::GradientShape;
+pub use gradient::EASE_IN_OUT_SEGMENTS;
+pub use gradient::MAX_GRADIENT_STEPS;
+pub use named::nearest_named_color;
+pub use named::NAMED_COLOR_THRESHOLD;
+pub use palette::load_palette;
+pub use parser::accent_color;
+pub use parser::classify_color;
+pub use parser::parse_color_layers;
+pub use parser::parse_color_string_or;
+pub use parser::parse_color_with_context;
+pub use parser::register_named_color;
+pub use parser::supported_formats;
+pub use parser::ColorKind;
+pub use solid::contrast_ratio;
+pub use solid::Channel;
 pub use solid::Solid;
+pub use solid::SolidImpl;
+pub use utils::desaturate;
+pub use utils::saturate;
+pub use utils::with_alpha;
 
 /// The `Color` enum represents different types of colors, including both solid colors and gradients.
 /// It can be either a solid color or a gradient, allowing flexibility in color representation.
+///
+/// Equality compares the color values only. Cached brushes are ignored.
 #[derive(Debug, Clone, PartialEq)]
 pub enum Color {
     /// Represents a solid color.
@@ -54,15 +108,89 @@ pub enum Color {
     Gradient(Gradient),
 }
 
+/// A wrapper around `Color` that implements `Eq` and `Hash`, so colors can be used as map keys.
+///
+/// The comparison is quantized rather than exact: every color channel, opacity, stop position and
+/// direction coordinate is clamped to 0.0 to 1.0 and rounded to a 16-bit value before comparing
+/// or hashing. Colors that differ by less than about 1/65535 per value therefore produce the same
+/// key, unless they fall on opposite sides of a rounding boundary. Brushes are ignored.
+///
+/// # Example
+/// ```rust
+/// # use std::collections::HashMap;
+/// # use win_color::{Color, ColorImpl, ColorKey};
+/// let mut cache = HashMap::new();
+/// cache.insert(ColorKey(Color::transparent()), "asset");
+/// ```
+#[derive(Debug, Clone)]
+pub struct ColorKey(pub Color);
+
+impl ColorKey {
+    /// Returns the quantized values the key is compared and hashed by.
+    fn quantized(&self) -> Vec<u16> {
+        let quantize = |value: f32| (value.clamp(0.0, 1.0) * u16::MAX as f32).round() as u16;
+        let channels = |color: &D2D1_COLOR_F| [color.r, color.g, color.b, color.a];
+
+        match &self.0 {
+            Color::Solid(solid) => channels(&solid.color)
+                .into_iter()
+                .chain([solid.opacity])
+                .map(quantize)
+                .chain([solid.none as u16])
+                .collect(),
+            Color::Gradient(gradient) => {
+                let mut values = vec![
+                    gradient.shape as u16,
+                    gradient.extend_mode as u16,
+                    gradient.gamma as u16,
+                    quantize(gradient.opacity),
+                ];
+                values.extend(
+                    gradient
+                        .direction
+                        .start
+                        .into_iter()
+                        .chain(gradient.direction.end)
+                        .map(quantize),
+                );
+                for stop in gradient.gradient_stops.iter() {
+                    values.push(quantize(stop.position));
+                    values.extend(channels(&stop.color).map(quantize));
+                }
+                values
+            }
+        }
+    }
+}
+
+impl PartialEq for ColorKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.is_gradient() == other.0.is_gradient() && self.quantized() == other.quantized()
+    }
+}
+
+impl Eq for ColorKey {}
+
+impl Hash for ColorKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.is_gradient().hash(state);
+        self.quantized().hash(state);
+    }
+}
+
 /// The `GlobalColor` enum represents a global color that can be either a color string (e.g., a hex color code or a color name)
 /// or a mapping to a gradient definition.
-#[derive(Debug, Clone, Deserialize, PartialEq)]
+///
+/// It is (de)serialized untagged: the string variant as a bare string and the mapping variant as an object.
+/// An integer such as `0xFF5733` is also accepted as an `0xRRGGBB` color and stored as its hex string.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 #[serde(untagged)]
 pub enum GlobalColor {
     /// A string representing a color, which could be a hex color code or a color name.
     ///
-    /// This variant is used for basic color definitions like `"#FF5733"` or `"red"`.
-    String(String),
+    /// This variant is used for basic color definitions like `"#FF5733"` or `"red"`, and for
+    /// integer colors, which are converted into a hex string when deserialized.
+    String(#[serde(deserialize_with = "deserialize_color_string")] String),
     /// A mapping defining a gradient color.
     ///
     /// This variant is used when the color is a gradient and contains a `ColorMapping` to define the gradient's
@@ -70,11 +198,38 @@ pub enum GlobalColor {
     Mapping(ColorMapping),
 }
 
+/// The shapes a `GlobalColor::String` is accepted in when deserializing.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ColorStringRepr {
+    String(String),
+    Integer(u32),
+}
+
+/// Deserializes a color string, converting an `0xRRGGBB` integer into its `#rrggbb` form.
+fn deserialize_color_string<'de, D>(deserializer: D) -> core::result::Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match ColorStringRepr::deserialize(deserializer)? {
+        ColorStringRepr::String(s) => Ok(s),
+        ColorStringRepr::Integer(value) if value <= 0xFFFFFF => Ok(format!("#{:06x}", value)),
+        ColorStringRepr::Integer(value) => Err(DeError::custom(format!(
+            "integer color {:#x} is larger than 0xFFFFFF",
+            value
+        ))),
+    }
+}
+
 impl Default for GlobalColor {
     fn default() -> Self {
-        // The default global color is black (`#000000`).
-        // This is the fallback color when no other color is specified.
-        Self::String("#000000".to_string())
+        // The default global color is the one configured with `set_default_color`, or black
+        // (`#000000`) if none was set. This is the fallback color when no other color is specified.
+        default_color()
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+            .unwrap_or_else(|| Self::String("#000000".to_string()))
     }
 }
 
@@ -88,14 +243,18 @@ impl Default for GlobalColor {
 /// and managing brush properties.
 ///
 /// # Methods
-/// - `fetch`: Tries to create a `Color` from a global color definition (`GlobalColor`).
-///   Optionally, a boolean flag (`is_active`) can be provided to modify the behavior.
+/// - `from_global_color`: Tries to create a `Color` from a global color definition (`GlobalColor`).
+///   Optionally, a boolean flag (`is_active`) can be provided to select the inactive colors.
 /// - `set_opacity`: Sets the opacity of the color, where `opacity` is a float value between 0.0 and 1.0.
+///   `try_set_opacity` rejects invalid values instead and `multiply_opacity` scales the current one.
 /// - `get_opacity`: Retrieves the current opacity of the color, if available.
 /// - `get_brush`: Returns an optional reference to the Direct2D brush associated with the color.
 /// - `set_transform`: Applies a transformation matrix to the color.
 /// - `to_d2d1_brush`: Converts the color to a Direct2D brush using the provided render target,
-///   window rectangle, and brush properties.
+///   window rectangle, and brush properties. `create_brush` returns a new brush without storing it.
+///
+/// It also provides color adjustments such as `darken`, `lighten` and `rotate_hue`, and accessors
+/// such as `is_solid` and `as_gradient`.
 pub trait ColorImpl {
     /// Attempts to create a `Color` from a global color definition.
     ///
@@ -105,19 +264,47 @@ pub trait ColorImpl {
     /// # Parameters
     /// - `color`: A reference to the `GlobalColor` definition.
     /// - `is_active`: An optional boolean that may affect the fetched color (e.g., active or inactive state).
+    ///   When it is `Some(false)`, the `accent` keyword resolves to the darkened accent color and
+    ///   mappings use their `inactive_colors` if present. `None` is treated as active.
     ///
     /// # Returns
-    /// A `Result` containing either the fetched `Color` or a `WinColorError` if the operation fails.
-    fn from_global_color(color: &GlobalColor) -> Result<Color>;
+    /// A `Result` containing either the fetched `Color` or an `Error` if the operation fails.
+    fn from_global_color(color: &GlobalColor, is_active: Option<bool>) -> Result<Color>;
 
     /// Sets the opacity of the color.
     ///
-    /// This method adjusts the opacity of the color to the specified `opacity` value.
+    /// This method adjusts the opacity of the color to the specified `opacity` value. It overrides
+    /// the brush opacity set from the `opacity` of the `Solid` or `Gradient` until the brush is
+    /// recreated.
     ///
     /// # Parameters
     /// - `opacity`: A floating-point value representing the opacity (from 0.0 for fully transparent to 1.0 for fully opaque).
+    ///   Values outside this range are clamped, and non-finite values such as `NaN` are ignored,
+    ///   leaving the brush unchanged. Use `try_set_opacity` to reject them instead.
     fn set_opacity(&self, opacity: f32);
 
+    /// Sets the opacity of the color, rejecting values outside 0.0 to 1.0.
+    ///
+    /// This behaves like `set_opacity`, but returns an error instead of clamping.
+    ///
+    /// # Parameters
+    /// - `opacity`: The opacity, from 0.0 for fully transparent to 1.0 for fully opaque.
+    ///
+    /// # Returns
+    /// A `Result` indicating success, or an `ErrorKind::InvalidInput` error if the opacity is
+    /// outside 0.0 to 1.0 or `NaN`. The brush is left unchanged on error.
+    fn try_set_opacity(&self, opacity: f32) -> Result<()>;
+
+    /// Multiplies the current opacity of the color by a factor.
+    ///
+    /// Unlike `set_opacity`, this scales the brush's existing opacity rather than replacing it,
+    /// so fading a color in or out preserves the opacity it was authored with. The result is
+    /// clamped to the range 0.0 to 1.0. As with `set_opacity`, a non-finite factor is ignored.
+    ///
+    /// # Parameters
+    /// - `factor`: The factor to multiply the current opacity by.
+    fn multiply_opacity(&self, factor: f32);
+
     /// Retrieves the current opacity of the color, if available.
     ///
     /// This method returns an `Option<f32>`, where `Some(f32)` indicates the opacity value,
@@ -138,51 +325,471 @@ pub trait ColorImpl {
     /// Applies a transformation matrix to the color.
     ///
     /// This method sets a transformation (e.g., scaling, rotation) on the color using the provided
-    /// transformation matrix.
+    /// transformation matrix. The transform is stored on the `Solid` or `Gradient` and applied to
+    /// the current brush, if any, and to every brush created afterwards.
     ///
     /// # Parameters
     /// - `transform`: A reference to the `Matrix3x2` transformation matrix that will be applied.
-    fn set_transform(&self, transform: &Matrix3x2);
+    fn set_transform(&mut self, transform: &Matrix3x2);
 
     /// Converts the color to a Direct2D brush.
     ///
     /// This method creates a Direct2D brush (`ID2D1Brush`) from the color, which can be used for rendering
     /// on a Direct2D render target. The brush is initialized with the given window rectangle and brush properties.
+    /// If a brush was already created from the same render target, color and brush properties, it is reused
+    /// and only its start and end points are updated for the window rectangle.
+    ///
+    /// A newly created brush is visible, using the opacity of the brush properties (multiplied by
+    /// `Solid::opacity` for solid colors), unless `initially_hidden` is set. A reused brush keeps
+    /// its current opacity. The stored `transform` of the `Solid` or `Gradient`, if any, replaces
+    /// the transform of the brush properties on a newly created brush.
     ///
     /// # Parameters
-    /// - `render_target`: The Direct2D render target on which the brush will be applied.
+    /// - `render_target`: The Direct2D render target on which the brush will be applied. Any render
+    ///   target can be passed, such as an `ID2D1HwndRenderTarget` or an `ID2D1DeviceContext`,
+    ///   since both dereference to `ID2D1RenderTarget`.
     /// - `window_rect`: The dimensions of the window, used to adjust the brush's rendering.
     /// - `brush_properties`: The properties that define how the brush will behave.
+    /// - `initially_hidden`: Whether a newly created brush starts with an opacity of 0.0, so it
+    ///   stays invisible until `set_opacity` is called.
+    ///
+    /// Brush colors are always passed to Direct2D with straight alpha, which it premultiplies
+    /// itself for render targets using `D2D1_ALPHA_MODE_PREMULTIPLIED`. Use
+    /// `SolidImpl::from_premultiplied` to convert colors read from premultiplied pixel data first.
     ///
     /// # Returns
-    /// A `WinResult<()>`, indicating success or failure.
+    /// A `Result<()>`, indicating success or failure. For the `none` color, no brush is created,
+    /// any existing brush is released and `Ok(())` is returned. If Direct2D fails to create the
+    /// brush, the error has the `BrushCreationFailed` kind and carries the Direct2D error as its
+    /// source. If a color channel is `NaN`, infinite or outside 0.0 to 1.0, an `InvalidData` error
+    /// is returned before any brush is created.
     fn to_d2d1_brush(
         &mut self,
-        render_target: &ID2D1HwndRenderTarget,
+        render_target: &ID2D1RenderTarget,
+        window_rect: &RECT,
+        brush_properties: &D2D1_BRUSH_PROPERTIES,
+        initially_hidden: bool,
+    ) -> Result<()>;
+
+    /// Creates a Direct2D brush from the color without storing it.
+    ///
+    /// This is the same brush `to_d2d1_brush` creates, with straight alpha colors, for callers
+    /// that manage the brush lifetime themselves. The color's `brush` is left untouched and the
+    /// brush is never reused, so each call creates a new one.
+    ///
+    /// # Parameters
+    /// - `render_target`: The Direct2D render target on which the brush will be applied.
+    /// - `window_rect`: The dimensions of the window, used for the start and end points of
+    ///   gradients.
+    /// - `brush_properties`: The properties that define how the brush will behave.
+    ///
+    /// Since a brush is always returned, the `none` color creates a fully transparent brush that
+    /// draws nothing. Check `is_none` first to skip drawing entirely.
+    ///
+    /// # Returns
+    /// A `Result` containing the visible brush, or an error as for `to_d2d1_brush`.
+    fn create_brush(
+        &self,
+        render_target: &ID2D1RenderTarget,
         window_rect: &RECT,
         brush_properties: &D2D1_BRUSH_PROPERTIES,
-    ) -> WinResult<()>;
+    ) -> Result<ID2D1Brush>;
+
+    /// Creates a solid color brush previewing the color, for example for small thumbnails.
+    ///
+    /// Solid colors create their normal brush, while gradients create a solid brush of their
+    /// `GradientImpl::average_color`, avoiding a gradient stop collection. The brush is not stored
+    /// in the color. As with `create_brush`, the `none` color creates a fully transparent brush.
+    ///
+    /// # Parameters
+    /// - `render_target`: The Direct2D render target on which the brush will be applied.
+    /// - `brush_properties`: The properties that define how the brush will behave.
+    ///
+    /// # Returns
+    /// A `Result` containing the new brush, or a `BrushCreationFailed` error if Direct2D fails to
+    /// create it and an `InvalidData` error if the color is invalid.
+    fn to_preview_solid(
+        &self,
+        render_target: &ID2D1RenderTarget,
+        brush_properties: &D2D1_BRUSH_PROPERTIES,
+    ) -> Result<ID2D1SolidColorBrush>;
+
+    /// Converts the color into a Win32 `COLORREF` value.
+    ///
+    /// For solid colors this is the color itself, and for gradients it is the color of the first
+    /// gradient stop. The alpha channel is dropped.
+    ///
+    /// # Returns
+    /// An `Option<u32>` containing the packed `0x00BBGGRR` value, or `None` if the gradient has no stops.
+    fn to_colorref(&self) -> Option<u32>;
+
+    /// Linearly interpolates between this color and another color.
+    ///
+    /// Two solid colors are interpolated channel-wise, along with their opacity. Two gradients
+    /// with the same number of stops are blended with `GradientImpl::blend`. The returned color
+    /// has no brush.
+    ///
+    /// # Parameters
+    /// - `other`: The color to interpolate towards.
+    /// - `t`: The interpolation factor, clamped to 0.0 (this color) to 1.0 (`other`).
+    ///
+    /// # Returns
+    /// A `Result` containing the interpolated `Color`, or an `ErrorKind::InvalidData` error if the
+    /// colors are a solid and a gradient, or gradients with different numbers of stops.
+    fn lerp(&self, other: &Color, t: f32) -> Result<Color>;
+
+    /// Returns a darkened copy of the color.
+    ///
+    /// Solid colors are darkened directly, and gradients have every stop's color darkened while
+    /// keeping their direction and stop positions. The returned color has no brush.
+    ///
+    /// # Parameters
+    /// - `percentage`: How much to reduce the lightness by, relative to its current value.
+    ///
+    /// # Returns
+    /// The darkened `Color`.
+    fn darken(&self, percentage: f32) -> Color;
+
+    /// Returns a lightened copy of the color.
+    ///
+    /// Solid colors are lightened directly, and gradients have every stop's color lightened while
+    /// keeping their direction and stop positions. The returned color has no brush.
+    ///
+    /// # Parameters
+    /// - `percentage`: How much to increase the lightness by, relative to its current value.
+    ///
+    /// # Returns
+    /// The lightened `Color`.
+    fn lighten(&self, percentage: f32) -> Color;
+
+    /// Returns a copy of the color darkened in linear light.
+    ///
+    /// Unlike `darken`, which adjusts the lightness of the sRGB-encoded channels, the channels are
+    /// linearized first and re-encoded afterwards, so mid-tones keep more of their brightness.
+    /// Gradients have every stop's color darkened. The returned color has no brush.
+    ///
+    /// # Parameters
+    /// - `percentage`: How much to reduce the linear lightness by, relative to its current value.
+    ///
+    /// # Returns
+    /// The darkened `Color`.
+    fn darken_linear(&self, percentage: f32) -> Color;
+
+    /// Returns a copy of the color lightened in linear light.
+    ///
+    /// Unlike `lighten`, which adjusts the lightness of the sRGB-encoded channels, the channels
+    /// are linearized first and re-encoded afterwards, so mid-tones brighten less strongly.
+    /// Gradients have every stop's color lightened. The returned color has no brush.
+    ///
+    /// # Parameters
+    /// - `percentage`: How much to increase the linear lightness by, relative to its current
+    ///   value.
+    ///
+    /// # Returns
+    /// The lightened `Color`.
+    fn lighten_linear(&self, percentage: f32) -> Color;
+
+    /// Returns a copy of the color with its hue rotated.
+    ///
+    /// The rotation goes through HSLA, so it is useful for deriving complementary or triadic
+    /// accents. Gradients have every stop's color rotated by the same amount. The returned color
+    /// has no brush.
+    ///
+    /// # Parameters
+    /// - `degrees`: The rotation in degrees. The resulting hue is wrapped into 0.0 to 360.0.
+    ///
+    /// # Returns
+    /// The rotated `Color`.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use win_color::{Color, ColorImpl};
+    /// # use windows::Win32::Graphics::Direct2D::Common::D2D1_COLOR_F;
+    /// let red = Color::from(D2D1_COLOR_F { r: 1.0, g: 0.0, b: 0.0, a: 1.0 });
+    /// let green = red.rotate_hue(120.0);
+    /// ```
+    fn rotate_hue(&self, degrees: f32) -> Color;
+
+    /// Forces the brush to be recreated on the next call to `to_d2d1_brush`.
+    ///
+    /// `to_d2d1_brush` reuses the existing brush when the render target, color and brush
+    /// properties are unchanged. This method discards that cached state; the current brush stays
+    /// available until it is replaced.
+    fn invalidate(&mut self);
+
+    /// Creates a fully transparent solid color.
+    ///
+    /// This is useful as a placeholder that draws nothing. `Color::default()` is transparent black
+    /// as well until `set_default_color` configures another default, while this always is.
+    ///
+    /// # Returns
+    /// A `Color::Solid` with every channel set to 0.0 and no brush.
+    fn transparent() -> Color;
+
+    /// Creates the `none` color, meaning the element should not be drawn at all.
+    ///
+    /// It is a fully transparent solid color, like `transparent`, but `to_d2d1_brush` creates no
+    /// brush for it. This is what `parse_color_string` returns for `none`.
+    ///
+    /// # Returns
+    /// A `Color::Solid` with every channel set to 0.0 and no brush, for which `is_none` is `true`.
+    fn none() -> Color;
+
+    /// Checks whether the color is the `none` color created by `none`.
+    ///
+    /// A `none` color is also transparent, but a transparent color is not necessarily `none`.
+    ///
+    /// # Returns
+    /// `true` if the color should not be drawn.
+    fn is_none(&self) -> bool;
+
+    /// Checks whether the color is fully transparent.
+    ///
+    /// # Returns
+    /// `true` if the solid color's alpha times its opacity is zero, or if every gradient stop's
+    /// alpha is zero.
+    fn is_transparent(&self) -> bool;
+
+    /// Returns a reversed copy of the color.
+    ///
+    /// Gradients are reversed with `GradientImpl::reverse`, and solid colors are returned
+    /// unchanged. The returned color has no brush.
+    ///
+    /// # Returns
+    /// The reversed `Color`.
+    fn reversed(&self) -> Color;
+
+    /// Compares two colors within a tolerance.
+    ///
+    /// Solid colors are compared channel-wise, along with their opacity. Gradients must have the
+    /// same shape and number of stops, and their stop positions, stop colors and direction are
+    /// compared within the tolerance. A solid color never equals a gradient. Brushes are ignored.
+    ///
+    /// # Parameters
+    /// - `other`: The color to compare with.
+    /// - `epsilon`: The largest allowed difference between any two values.
+    ///
+    /// # Returns
+    /// `true` if the colors are equal within `epsilon`.
+    fn approx_eq(&self, other: &Color, epsilon: f32) -> bool;
+
+    /// Collapses the color into a representative solid color, for render paths that cannot use
+    /// gradients.
+    ///
+    /// # Returns
+    /// A copy of a solid color, or the color in the middle of a gradient as given by
+    /// `GradientImpl::color_at(0.5)`. The returned `Solid` has no brush.
+    fn to_solid_fallback(&self) -> Solid;
+
+    /// Converts gradients that show a single color into a solid color, which is cheaper to
+    /// render.
+    ///
+    /// A gradient is converted when `GradientImpl::is_effectively_solid` returns `true`, keeping
+    /// the first stop's color and the gradient's opacity and transform. Other colors are returned
+    /// unchanged.
+    ///
+    /// # Returns
+    /// The simplified `Color`. A converted color has no brush.
+    fn simplify(self) -> Color;
+
+    /// Converts the color back into a `GlobalColor`, for saving it to a config.
+    ///
+    /// Solid colors become a `GlobalColor::String` holding their hex color, with `Solid::opacity`
+    /// multiplied into the alpha as `Display` does, and the `none` color becomes `none`. Gradients
+    /// become a `GlobalColor::Mapping` in the form returned by `ColorMappingImpl::canonicalize`: a
+    /// `#rrggbbaa` color per stop, positions unless the stops are evenly spaced, the coordinates
+    /// rounded to four decimal places as the direction, and the gradient's opacity.
+    ///
+    /// A `ColorMapping` always describes a plain linear gradient, so gradients it can't represent
+    /// are rejected instead of being converted with data loss. The transform of a solid color is
+    /// not preserved, since it doesn't change how the color is drawn.
+    ///
+    /// # Returns
+    /// A `Result` containing the `GlobalColor`, which converts back into an equivalent color with
+    /// `to_color`, or an `ErrorKind::InvalidData` error for a gradient that is not linear, uses an
+    /// extend mode other than `Clamp` or a gamma other than `Srgb`, or has a transform.
+    fn to_global_color(&self) -> Result<GlobalColor>;
+
+    /// Updates the brush for a new window size.
+    ///
+    /// For gradients with a brush, the start and end points are recomputed in pixels for the new
+    /// window rectangle with `GradientImpl::update_start_end_points`. Solid colors do not depend
+    /// on the window size and are unaffected.
+    ///
+    /// # Parameters
+    /// - `window_rect`: The new dimensions of the window.
+    fn on_resize(&self, window_rect: &RECT);
+
+    /// Returns `true` if the color is a `Color::Solid`.
+    fn is_solid(&self) -> bool;
+
+    /// Returns `true` if the color is a `Color::Gradient`.
+    fn is_gradient(&self) -> bool;
+
+    /// Returns a reference to the `Solid` if the color is a `Color::Solid`.
+    fn as_solid(&self) -> Option<&Solid>;
+
+    /// Returns a mutable reference to the `Solid` if the color is a `Color::Solid`.
+    fn as_solid_mut(&mut self) -> Option<&mut Solid>;
+
+    /// Returns a reference to the `Gradient` if the color is a `Color::Gradient`.
+    fn as_gradient(&self) -> Option<&Gradient>;
+
+    /// Returns a mutable reference to the `Gradient` if the color is a `Color::Gradient`.
+    fn as_gradient_mut(&mut self) -> Option<&mut Gradient>;
 }
 
 pub trait GlobalColorImpl {
-    fn to_color(&self) -> Result<Color>;
+    /// Creates a `GlobalColor` from a string that holds either a color or a JSON mapping.
+    ///
+    /// Input starting with `{` is parsed as a JSON `ColorMapping`, and anything else becomes a
+    /// `GlobalColor::String`. This suits sources where every value arrives as a string, such as
+    /// command line arguments or environment variables. The color itself is not validated until
+    /// `to_color` is called.
+    ///
+    /// # Parameters
+    /// - `input`: The color string or JSON object. Surrounding whitespace is ignored.
+    ///
+    /// # Returns
+    /// A `Result` containing the `GlobalColor`, or an `ErrorKind::InvalidInput` error if the
+    /// input looks like a JSON object but is not a valid `ColorMapping`.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use win_color::{GlobalColor, GlobalColorImpl};
+    /// # fn main() -> win_color::Result<()> {
+    /// let color = GlobalColor::parse(r##"{ "colors": ["#f00", "#00f"], "direction": "to right" }"##)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn parse(input: &str) -> Result<GlobalColor>
+    where
+        Self: Sized;
+
+    fn to_color(&self, is_active: Option<bool>) -> Result<Color>;
 }
 
 impl GlobalColorImpl for GlobalColor {
-    fn to_color(&self) -> Result<Color> {
+    fn parse(input: &str) -> Result<GlobalColor> {
+        let input = input.trim();
+
+        if !input.starts_with('{') {
+            return Ok(GlobalColor::String(input.to_string()));
+        }
+
+        serde_json::from_str(input)
+            .map(GlobalColor::Mapping)
+            .map_err(|e| {
+                Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("Invalid color mapping: {}", e),
+                )
+            })
+    }
+
+    fn to_color(&self, is_active: Option<bool>) -> Result<Color> {
         match self {
-            GlobalColor::String(s) => parse_color_string(s.as_str()),
-            GlobalColor::Mapping(gradient_def) => parse_color_mapping(gradient_def.clone()),
+            GlobalColor::String(s) => parse_color_string(s.as_str(), is_active),
+            GlobalColor::Mapping(gradient_def) => {
+                parse_color_mapping(gradient_def.clone(), is_active)
+            }
+        }
+    }
+}
+
+/// Resolves the first color definition that converts successfully.
+///
+/// This is useful for fallback chains, such as preferring the accent color but falling back to a
+/// fixed color on systems where it cannot be retrieved.
+///
+/// # Parameters
+/// - `candidates`: The color definitions to try, in order.
+/// - `is_active`: An optional flag indicating whether the color is active, passed to
+///   `GlobalColorImpl::to_color`.
+///
+/// # Returns
+/// A `Result` containing the first successfully converted `Color`, or an `ErrorKind::InvalidInput`
+/// error listing every failure if none of the candidates convert.
+///
+/// # Example
+/// ```rust
+/// # use win_color::{resolve_first, GlobalColor};
+/// # fn main() -> win_color::Result<()> {
+/// let candidates = [
+///     GlobalColor::String("accent".to_string()),
+///     GlobalColor::String("#0078d4".to_string()),
+/// ];
+/// let color = resolve_first(&candidates, None)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn resolve_first(candidates: &[GlobalColor], is_active: Option<bool>) -> Result<Color> {
+    let mut failures = Vec::with_capacity(candidates.len());
+
+    for (i, candidate) in candidates.iter().enumerate() {
+        match candidate.to_color(is_active) {
+            Ok(color) => return Ok(color),
+            Err(e) => failures.push(format!("candidate {}: {}", i, e)),
         }
     }
+
+    if failures.is_empty() {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "No color candidates given",
+        ));
+    }
+
+    Err(Error::new(
+        ErrorKind::InvalidInput,
+        format!(
+            "No color candidate could be resolved ({})",
+            failures.join("; ")
+        ),
+    ))
+}
+
+/// Sets the color returned by `GlobalColor::default` and `Color::default`.
+///
+/// The default color is stored in a process-wide global, so it affects every caller of the
+/// `Default` impls in the process, including other libraries that use this crate. Setting it
+/// again replaces the previous value. Until it is set, `GlobalColor::default` is `#000000` and
+/// `Color::default` is transparent black.
+///
+/// # Parameters
+/// - `color`: The color definition to use as the default.
+///
+/// # Example
+/// ```rust
+/// # use win_color::{set_default_color, Color, GlobalColor};
+/// set_default_color(GlobalColor::String("#1e1e2e".to_string()));
+/// let color = Color::default();
+/// ```
+pub fn set_default_color(color: GlobalColor) {
+    let mut default = default_color()
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    *default = Some(color);
+}
+
+/// Returns the default color configured with `set_default_color`, if any.
+fn default_color() -> &'static RwLock<Option<GlobalColor>> {
+    static DEFAULT_COLOR: OnceLock<RwLock<Option<GlobalColor>>> = OnceLock::new();
+    DEFAULT_COLOR.get_or_init(|| RwLock::new(None))
 }
 
 impl ColorImpl for Color {
-    fn from_global_color(global_color: &GlobalColor) -> Result<Self> {
-        global_color.to_color()
+    fn from_global_color(global_color: &GlobalColor, is_active: Option<bool>) -> Result<Self> {
+        global_color.to_color(is_active)
     }
 
     fn set_opacity(&self, opacity: f32) {
+        if !opacity.is_finite() {
+            return;
+        }
+
+        let opacity = opacity.clamp(0.0, 1.0);
+
         match self {
             Color::Gradient(gradient) => {
                 if let Some(ref id2d1_brush) = gradient.brush {
@@ -197,6 +804,32 @@ impl ColorImpl for Color {
         }
     }
 
+    fn try_set_opacity(&self, opacity: f32) -> Result<()> {
+        if !(0.0..=1.0).contains(&opacity) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("The opacity {} is not within 0.0 to 1.0", opacity),
+            ));
+        }
+
+        self.set_opacity(opacity);
+
+        Ok(())
+    }
+
+    fn multiply_opacity(&self, factor: f32) {
+        if !factor.is_finite() {
+            return;
+        }
+
+        if let Some(id2d1_brush) = self.get_brush() {
+            unsafe {
+                let opacity = id2d1_brush.GetOpacity() * factor;
+                id2d1_brush.SetOpacity(opacity.clamp(0.0, 1.0));
+            }
+        }
+    }
+
     fn get_opacity(&self) -> Option<f32> {
         match self {
             Color::Solid(solid) => solid
@@ -210,9 +843,10 @@ impl ColorImpl for Color {
         }
     }
 
-    fn set_transform(&self, transform: &Matrix3x2) {
+    fn set_transform(&mut self, transform: &Matrix3x2) {
         match self {
             Color::Solid(solid) => {
+                solid.transform = Some(*transform);
                 if let Some(ref id2d1_brush) = solid.brush {
                     unsafe {
                         id2d1_brush.SetTransform(transform);
@@ -220,6 +854,7 @@ impl ColorImpl for Color {
                 }
             }
             Color::Gradient(gradient) => {
+                gradient.transform = Some(*transform);
                 if let Some(ref id2d1_brush) = gradient.brush {
                     unsafe {
                         id2d1_brush.SetTransform(transform);
@@ -232,71 +867,1930 @@ impl ColorImpl for Color {
     fn get_brush(&self) -> Option<&ID2D1Brush> {
         match self {
             Color::Solid(solid) => solid.brush.as_ref().map(|id2d1_brush| id2d1_brush.into()),
-            Color::Gradient(gradient) => gradient
-                .brush
-                .as_ref()
-                .map(|id2d1_brush| id2d1_brush.into()),
+            Color::Gradient(gradient) => gradient.brush.as_ref(),
         }
     }
 
     fn to_d2d1_brush(
         &mut self,
-        render_target: &ID2D1HwndRenderTarget,
+        render_target: &ID2D1RenderTarget,
         window_rect: &RECT,
         brush_properties: &D2D1_BRUSH_PROPERTIES,
-    ) -> WinResult<()> {
+        initially_hidden: bool,
+    ) -> Result<()> {
+        let brush_key = brush_key(self, brush_properties);
+
         match self {
+            Color::Solid(solid) if solid.none => {
+                solid.brush = None;
+                solid.brush_key = None;
+                solid.brush_target = None;
+
+                Ok(())
+            }
             Color::Solid(solid) => unsafe {
-                let id2d1_brush =
-                    render_target.CreateSolidColorBrush(&solid.color, Some(brush_properties))?;
+                if solid.brush.is_some()
+                    && solid.brush_key == Some(brush_key)
+                    && is_same_render_target(solid.brush_target.as_ref(), render_target)
+                {
+                    return Ok(());
+                }
 
-                id2d1_brush.SetOpacity(0.0);
+                let id2d1_brush = create_solid_brush(solid, render_target, brush_properties)?;
+
+                if initially_hidden {
+                    id2d1_brush.SetOpacity(0.0);
+                }
 
                 solid.brush = Some(id2d1_brush);
+                solid.brush_key = Some(brush_key);
+                solid.brush_target = Some(render_target.clone());
 
                 Ok(())
             },
             Color::Gradient(gradient) => unsafe {
-                let width = (window_rect.right - window_rect.left) as f32;
-                let height = (window_rect.bottom - window_rect.top) as f32;
-
-                let gradient_properties = D2D1_LINEAR_GRADIENT_BRUSH_PROPERTIES {
-                    startPoint: D2D_POINT_2F {
-                        x: gradient.direction.start[0] * width,
-                        y: gradient.direction.start[1] * height,
-                    },
-                    endPoint: D2D_POINT_2F {
-                        x: gradient.direction.end[0] * width,
-                        y: gradient.direction.end[1] * height,
-                    },
-                };
+                if gradient.brush.is_some()
+                    && gradient.brush_key == Some(brush_key)
+                    && is_same_render_target(gradient.brush_target.as_ref(), render_target)
+                {
+                    gradient.update_start_end_points(window_rect);
+                    return Ok(());
+                }
 
-                let gradient_stop_collection = render_target.CreateGradientStopCollection(
-                    &gradient.gradient_stops,
-                    D2D1_GAMMA_2_2,
-                    D2D1_EXTEND_MODE_CLAMP,
-                )?;
+                let id2d1_brush =
+                    create_gradient_brush(gradient, render_target, window_rect, brush_properties)?;
 
-                let id2d1_brush = render_target.CreateLinearGradientBrush(
-                    &gradient_properties,
-                    Some(brush_properties),
-                    &gradient_stop_collection,
-                )?;
+                if initially_hidden {
+                    id2d1_brush.SetOpacity(0.0);
+                }
 
-                id2d1_brush.SetOpacity(0.0);
                 gradient.brush = Some(id2d1_brush);
+                gradient.brush_key = Some(brush_key);
+                gradient.brush_target = Some(render_target.clone());
 
                 Ok(())
             },
         }
     }
-}
 
-impl Default for Color {
-    fn default() -> Self {
-        Color::Solid(Solid {
-            color: D2D1_COLOR_F::default(),
-            brush: None,
-        })
+    fn create_brush(
+        &self,
+        render_target: &ID2D1RenderTarget,
+        window_rect: &RECT,
+        brush_properties: &D2D1_BRUSH_PROPERTIES,
+    ) -> Result<ID2D1Brush> {
+        match self {
+            Color::Solid(solid) => create_solid_brush(solid, render_target, brush_properties)
+                .map(|id2d1_brush| id2d1_brush.into()),
+            Color::Gradient(gradient) => {
+                create_gradient_brush(gradient, render_target, window_rect, brush_properties)
+            }
+        }
+    }
+
+    fn to_preview_solid(
+        &self,
+        render_target: &ID2D1RenderTarget,
+        brush_properties: &D2D1_BRUSH_PROPERTIES,
+    ) -> Result<ID2D1SolidColorBrush> {
+        let solid = match self {
+            Color::Solid(solid) => Solid {
+                opacity: solid.opacity,
+                ..Solid::from(solid.color)
+            },
+            Color::Gradient(gradient) => Solid::from(gradient.average_color()),
+        };
+        solid.validate()?;
+
+        unsafe {
+            let id2d1_brush = render_target
+                .CreateSolidColorBrush(&solid.color, Some(brush_properties))
+                .map_err(|e| Error::from_windows(ErrorKind::BrushCreationFailed, e))?;
+            id2d1_brush.SetOpacity(brush_properties.opacity * solid.opacity);
+
+            Ok(id2d1_brush)
+        }
+    }
+
+    fn to_colorref(&self) -> Option<u32> {
+        match self {
+            Color::Solid(solid) => Some(solid.to_colorref()),
+            Color::Gradient(gradient) => gradient
+                .gradient_stops
+                .first()
+                .map(|stop| Solid::from(stop.color).to_colorref()),
+        }
+    }
+
+    fn lerp(&self, other: &Color, t: f32) -> Result<Color> {
+        let t = t.clamp(0.0, 1.0);
+
+        match (self, other) {
+            (Color::Solid(from), Color::Solid(to)) => Ok(Color::Solid(Solid {
+                opacity: lerp(from.opacity, to.opacity, t),
+                ..Solid::from(lerp_color(&from.color, &to.color, t))
+            })),
+            (Color::Gradient(from), Color::Gradient(to)) => from.blend(to, t).map(Color::Gradient),
+            _ => Err(Error::new(
+                ErrorKind::InvalidData,
+                "Cannot interpolate between a solid color and a gradient",
+            )),
+        }
+    }
+
+    fn darken(&self, percentage: f32) -> Color {
+        self.map_colors(|color| darken(color, percentage))
+    }
+
+    fn lighten(&self, percentage: f32) -> Color {
+        self.map_colors(|color| lighten(color, percentage))
+    }
+
+    fn darken_linear(&self, percentage: f32) -> Color {
+        self.map_colors(|color| darken_linear(color, percentage))
+    }
+
+    fn lighten_linear(&self, percentage: f32) -> Color {
+        self.map_colors(|color| lighten_linear(color, percentage))
+    }
+
+    fn rotate_hue(&self, degrees: f32) -> Color {
+        self.map_colors(|color| rotate_hue(color, degrees))
+    }
+
+    fn invalidate(&mut self) {
+        match self {
+            Color::Solid(solid) => solid.brush_key = None,
+            Color::Gradient(gradient) => gradient.brush_key = None,
+        }
+    }
+
+    fn transparent() -> Color {
+        Color::Solid(Solid::from(D2D1_COLOR_F {
+            r: 0.0,
+            g: 0.0,
+            b: 0.0,
+            a: 0.0,
+        }))
+    }
+
+    fn none() -> Color {
+        Color::Solid(Solid {
+            none: true,
+            ..Solid::from(D2D1_COLOR_F::default())
+        })
+    }
+
+    fn is_none(&self) -> bool {
+        matches!(self, Color::Solid(solid) if solid.none)
+    }
+
+    fn is_transparent(&self) -> bool {
+        match self {
+            Color::Solid(solid) => solid.effective_color().a == 0.0,
+            Color::Gradient(gradient) => gradient
+                .gradient_stops
+                .iter()
+                .all(|stop| stop.color.a == 0.0),
+        }
+    }
+
+    fn reversed(&self) -> Color {
+        match self {
+            Color::Solid(solid) => Color::Solid(Solid {
+                opacity: solid.opacity,
+                transform: solid.transform,
+                none: solid.none,
+                ..Solid::from(solid.color)
+            }),
+            Color::Gradient(gradient) => {
+                let mut gradient = Gradient {
+                    brush: None,
+                    brush_key: None,
+                    brush_target: None,
+                    ..gradient.clone()
+                };
+                gradient.reverse();
+                Color::Gradient(gradient)
+            }
+        }
+    }
+
+    fn simplify(self) -> Color {
+        match self {
+            Color::Gradient(gradient) if gradient.is_effectively_solid() => Color::Solid(Solid {
+                opacity: gradient.opacity,
+                transform: gradient.transform,
+                ..Solid::from(gradient.gradient_stops[0].color)
+            }),
+            color => color,
+        }
+    }
+
+    fn to_global_color(&self) -> Result<GlobalColor> {
+        match self {
+            Color::Solid(solid) if solid.none => Ok(GlobalColor::String("none".to_string())),
+            Color::Solid(solid) => Ok(GlobalColor::String(to_hex(&solid.effective_color()))),
+            Color::Gradient(gradient) => {
+                let unsupported = if gradient.shape != GradientShape::Linear {
+                    Some(format!("{:?} shape", gradient.shape))
+                } else if gradient.extend_mode != GradientExtendMode::Clamp {
+                    Some(format!("{:?} extend mode", gradient.extend_mode))
+                } else if gradient.gamma != GradientGamma::Srgb {
+                    Some(format!("{:?} gamma", gradient.gamma))
+                } else if gradient.transform.is_some() {
+                    Some("transform".to_string())
+                } else {
+                    None
+                };
+
+                if let Some(unsupported) = unsupported {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        format!(
+                            "A color mapping cannot represent the gradient's {}",
+                            unsupported
+                        ),
+                    ));
+                }
+
+                Ok(GlobalColor::Mapping(ColorMapping {
+                    colors: gradient
+                        .gradient_stops
+                        .iter()
+                        .map(|stop| to_hex_rgba(&stop.color))
+                        .collect(),
+                    inactive_colors: None,
+                    positions: Some(
+                        gradient
+                            .gradient_stops
+                            .iter()
+                            .map(|stop| stop.position)
+                            .collect::<Vec<_>>(),
+                    )
+                    .filter(|positions| !is_evenly_spaced(positions)),
+                    interpolation: GradientInterpolation::Linear,
+                    direction: GradientDirection::Coordinates(round_coordinates(
+                        &gradient.direction,
+                    )),
+                    opacity: (gradient.opacity != 1.0).then_some(gradient.opacity),
+                }))
+            }
+        }
+    }
+
+    fn approx_eq(&self, other: &Color, epsilon: f32) -> bool {
+        let close = |a: f32, b: f32| (a - b).abs() <= epsilon;
+        let colors_close = |a: &D2D1_COLOR_F, b: &D2D1_COLOR_F| {
+            close(a.r, b.r) && close(a.g, b.g) && close(a.b, b.b) && close(a.a, b.a)
+        };
+
+        match (self, other) {
+            (Color::Solid(a), Color::Solid(b)) => {
+                colors_close(&a.color, &b.color) && close(a.opacity, b.opacity)
+            }
+            (Color::Gradient(a), Color::Gradient(b)) => {
+                let direction_close = a
+                    .direction
+                    .start
+                    .iter()
+                    .chain(a.direction.end.iter())
+                    .zip(b.direction.start.iter().chain(b.direction.end.iter()))
+                    .all(|(a, b)| close(*a, *b));
+
+                a.shape == b.shape
+                    && close(a.opacity, b.opacity)
+                    && direction_close
+                    && a.gradient_stops.len() == b.gradient_stops.len()
+                    && a.gradient_stops
+                        .iter()
+                        .zip(b.gradient_stops.iter())
+                        .all(|(a, b)| {
+                            close(a.position, b.position) && colors_close(&a.color, &b.color)
+                        })
+            }
+            _ => false,
+        }
+    }
+
+    fn to_solid_fallback(&self) -> Solid {
+        match self {
+            Color::Solid(solid) => Solid {
+                opacity: solid.opacity,
+                ..Solid::from(solid.color)
+            },
+            Color::Gradient(gradient) => Solid::from(gradient.color_at(0.5)),
+        }
+    }
+
+    fn on_resize(&self, window_rect: &RECT) {
+        if let Color::Gradient(gradient) = self {
+            gradient.update_start_end_points(window_rect);
+        }
+    }
+
+    fn is_solid(&self) -> bool {
+        matches!(self, Color::Solid(_))
+    }
+
+    fn is_gradient(&self) -> bool {
+        matches!(self, Color::Gradient(_))
+    }
+
+    fn as_solid(&self) -> Option<&Solid> {
+        match self {
+            Color::Solid(solid) => Some(solid),
+            Color::Gradient(_) => None,
+        }
+    }
+
+    fn as_solid_mut(&mut self) -> Option<&mut Solid> {
+        match self {
+            Color::Solid(solid) => Some(solid),
+            Color::Gradient(_) => None,
+        }
+    }
+
+    fn as_gradient(&self) -> Option<&Gradient> {
+        match self {
+            Color::Gradient(gradient) => Some(gradient),
+            Color::Solid(_) => None,
+        }
+    }
+
+    fn as_gradient_mut(&mut self) -> Option<&mut Gradient> {
+        match self {
+            Color::Gradient(gradient) => Some(gradient),
+            Color::Solid(_) => None,
+        }
+    }
+}
+
+impl Color {
+    /// Returns a copy of the color with `f` applied to the solid color or every gradient stop's
+    /// color. The returned color has no brush.
+    pub(crate) fn map_colors(&self, f: impl Fn(D2D1_COLOR_F) -> D2D1_COLOR_F) -> Color {
+        match self {
+            Color::Solid(solid) => Color::Solid(Solid {
+                opacity: solid.opacity,
+                transform: solid.transform,
+                none: solid.none,
+                ..Solid::from(f(solid.color))
+            }),
+            Color::Gradient(gradient) => Color::Gradient(Gradient {
+                direction: gradient.direction.clone(),
+                gradient_stops: gradient
+                    .gradient_stops
+                    .iter()
+                    .map(|stop| D2D1_GRADIENT_STOP {
+                        position: stop.position,
+                        color: f(stop.color),
+                    })
+                    .collect(),
+                shape: gradient.shape,
+                extend_mode: gradient.extend_mode,
+                gamma: gradient.gamma,
+                opacity: gradient.opacity,
+                transform: gradient.transform,
+                brush: None,
+                brush_key: None,
+                brush_target: None,
+            }),
+        }
+    }
+}
+
+/// Creates a visible brush for a solid color, applying its opacity and stored transform.
+///
+/// The color is validated first.
+fn create_solid_brush(
+    solid: &Solid,
+    render_target: &ID2D1RenderTarget,
+    brush_properties: &D2D1_BRUSH_PROPERTIES,
+) -> Result<ID2D1SolidColorBrush> {
+    solid.validate()?;
+
+    unsafe {
+        let id2d1_brush = render_target
+            .CreateSolidColorBrush(&solid.color, Some(brush_properties))
+            .map_err(|e| Error::from_windows(ErrorKind::BrushCreationFailed, e))?;
+
+        id2d1_brush.SetOpacity(brush_properties.opacity * solid.opacity);
+
+        if let Some(ref transform) = solid.transform {
+            id2d1_brush.SetTransform(transform);
+        }
+
+        Ok(id2d1_brush)
+    }
+}
+
+/// Creates a visible brush for a gradient, applying its opacity and stored transform.
+///
+/// The stop colors are validated first. The start and end points are computed for
+/// `window_rect`.
+fn create_gradient_brush(
+    gradient: &Gradient,
+    render_target: &ID2D1RenderTarget,
+    window_rect: &RECT,
+    brush_properties: &D2D1_BRUSH_PROPERTIES,
+) -> Result<ID2D1Brush> {
+    let brush_error = |e| Error::from_windows(ErrorKind::BrushCreationFailed, e);
+
+    for stop in gradient.gradient_stops.iter() {
+        validate_color(&stop.color)?;
+    }
+
+    let (start_point, end_point) = gradient.pixel_points(window_rect);
+
+    unsafe {
+        let gradient_stop_collection = render_target
+            .CreateGradientStopCollection(
+                &gradient.brush_stops(),
+                gradient.gamma.into(),
+                gradient.extend_mode.into(),
+            )
+            .map_err(brush_error)?;
+
+        let id2d1_brush: ID2D1Brush = match gradient.shape {
+            GradientShape::Linear | GradientShape::Conic => {
+                let gradient_properties = D2D1_LINEAR_GRADIENT_BRUSH_PROPERTIES {
+                    startPoint: start_point,
+                    endPoint: end_point,
+                };
+
+                render_target
+                    .CreateLinearGradientBrush(
+                        &gradient_properties,
+                        Some(brush_properties),
+                        &gradient_stop_collection,
+                    )
+                    .map_err(brush_error)?
+                    .into()
+            }
+            GradientShape::Radial => {
+                // The start point is the center and the distance to the end point
+                // gives the radii along each axis.
+                let gradient_properties = D2D1_RADIAL_GRADIENT_BRUSH_PROPERTIES {
+                    center: start_point,
+                    gradientOriginOffset: D2D_POINT_2F::default(),
+                    radiusX: (end_point.x - start_point.x).abs(),
+                    radiusY: (end_point.y - start_point.y).abs(),
+                };
+
+                render_target
+                    .CreateRadialGradientBrush(
+                        &gradient_properties,
+                        Some(brush_properties),
+                        &gradient_stop_collection,
+                    )
+                    .map_err(brush_error)?
+                    .into()
+            }
+        };
+
+        id2d1_brush.SetOpacity(brush_properties.opacity * gradient.opacity);
+
+        if let Some(ref transform) = gradient.transform {
+            id2d1_brush.SetTransform(transform);
+        }
+
+        Ok(id2d1_brush)
+    }
+}
+
+/// Computes a hash of the inputs a brush is created from.
+///
+/// The hash covers the brush properties and the color itself (the solid color, or the gradient's
+/// shape, extend mode, gamma, opacity and stops). A stored transform is hashed in place of the
+/// transform of the brush properties. The gradient direction is excluded since it only affects
+/// the start and end points, which can be updated on an existing brush. The render target is
+/// compared separately with `is_same_render_target`.
+fn brush_key(color: &Color, brush_properties: &D2D1_BRUSH_PROPERTIES) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    let mut values = Vec::new();
+
+    match color {
+        Color::Solid(solid) => {
+            let color = &solid.color;
+            values.extend([color.r, color.g, color.b, color.a, solid.opacity]);
+        }
+        Color::Gradient(gradient) => {
+            for stop in gradient.gradient_stops.iter() {
+                let color = &stop.color;
+                values.extend([stop.position, color.r, color.g, color.b, color.a]);
+            }
+            gradient.shape.hash(&mut hasher);
+            gradient.extend_mode.hash(&mut hasher);
+            gradient.gamma.hash(&mut hasher);
+            values.push(gradient.opacity);
+        }
+    }
+
+    let transform = match color {
+        Color::Solid(solid) => solid.transform.as_ref(),
+        Color::Gradient(gradient) => gradient.transform.as_ref(),
+    }
+    .unwrap_or(&brush_properties.transform);
+    values.extend([
+        brush_properties.opacity,
+        transform.M11,
+        transform.M12,
+        transform.M21,
+        transform.M22,
+        transform.M31,
+        transform.M32,
+    ]);
+
+    values
+        .iter()
+        .for_each(|value| value.to_bits().hash(&mut hasher));
+
+    hasher.finish()
+}
+
+/// Checks whether a brush was created on the given render target.
+///
+/// The targets are compared by COM identity rather than by address. The brush keeps its target
+/// alive, so a new target can never take over its address while the brush is cached.
+fn is_same_render_target(
+    brush_target: Option<&ID2D1RenderTarget>,
+    render_target: &ID2D1RenderTarget,
+) -> bool {
+    brush_target.is_some_and(|brush_target| {
+        matches!(
+            (brush_target.cast::<IUnknown>(), render_target.cast::<IUnknown>()),
+            (Ok(a), Ok(b)) if a == b
+        )
+    })
+}
+
+impl Default for Color {
+    fn default() -> Self {
+        // Falls back to the built-in default if the color configured with `set_default_color`
+        // cannot be converted, so `default` never fails.
+        let configured = default_color()
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone();
+
+        configured
+            .and_then(|color| color.to_color(None).ok())
+            .unwrap_or_else(|| Color::Solid(Solid::from(D2D1_COLOR_F::default())))
+    }
+}
+
+impl From<D2D1_COLOR_F> for Color {
+    fn from(color: D2D1_COLOR_F) -> Self {
+        Color::Solid(Solid::from(color))
+    }
+}
+
+impl From<(f32, f32, f32, f32)> for Color {
+    /// Creates a solid color from an `(r, g, b, a)` tuple, clamping each channel to 0.0 to 1.0.
+    fn from((r, g, b, a): (f32, f32, f32, f32)) -> Self {
+        Color::from(D2D1_COLOR_F {
+            r: r.clamp(0.0, 1.0),
+            g: g.clamp(0.0, 1.0),
+            b: b.clamp(0.0, 1.0),
+            a: a.clamp(0.0, 1.0),
+        })
+    }
+}
+
+impl core::fmt::Display for Color {
+    /// Formats the `Color` as a CSS-like string that can be parsed back by `GlobalColor::String`.
+    ///
+    /// Solid colors are formatted as `#RRGGBB`, or `#RRGGBBAA` when not fully opaque, with
+    /// `Solid::opacity` multiplied into the alpha, and the `none` color as `none`. Gradients are
+    /// formatted with the function matching their shape, `linear-gradient(<direction>, <stops>)`,
+    /// `radial-gradient(<stops>)` or `conic-gradient(from <angle>, <stops>)`, prefixed with
+    /// `repeating-` when they use the `Wrap` extend mode. Every stop is followed by its position as a percentage, and the
+    /// gradient's opacity is multiplied into the alpha of its stops.
+    ///
+    /// The CSS syntax cannot express everything a `Gradient` holds: linear directions are reduced
+    /// to their angle, radial gradients are always centered in the window, and the `Mirror`
+    /// extend mode, the gamma and the transform are not preserved.
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Color::Solid(solid) if solid.none => write!(f, "none"),
+            Color::Solid(solid) => write!(f, "{}", to_hex(&solid.effective_color())),
+            Color::Gradient(gradient) => {
+                let stops = gradient
+                    .gradient_stops
+                    .iter()
+                    .map(|stop| {
+                        let color = D2D1_COLOR_F {
+                            a: stop.color.a * gradient.opacity,
+                            ..stop.color
+                        };
+                        format!("{} {}%", to_hex(&color), stop.position * 100.0)
+                    })
+                    .collect::<Vec<String>>()
+                    .join(", ");
+
+                if gradient.extend_mode == GradientExtendMode::Wrap {
+                    write!(f, "repeating-")?;
+                }
+
+                match gradient.shape {
+                    GradientShape::Linear => write!(
+                        f,
+                        "linear-gradient({}, {})",
+                        format_direction(&gradient.direction),
+                        stops
+                    ),
+                    GradientShape::Radial => write!(f, "radial-gradient({})", stops),
+                    GradientShape::Conic => write!(
+                        f,
+                        "conic-gradient(from {}deg, {})",
+                        (direction_angle(&gradient.direction) * 100.0).round() / 100.0,
+                        stops
+                    ),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Creates a software render target for tests that create brushes.
+    #[cfg(windows)]
+    pub(crate) fn render_target() -> ID2D1RenderTarget {
+        use windows::Win32::Graphics::Direct2D::Common::D2D1_ALPHA_MODE_PREMULTIPLIED;
+        use windows::Win32::Graphics::Direct2D::Common::D2D1_PIXEL_FORMAT;
+        use windows::Win32::Graphics::Direct2D::D2D1CreateFactory;
+        use windows::Win32::Graphics::Direct2D::ID2D1Factory;
+        use windows::Win32::Graphics::Direct2D::D2D1_FACTORY_TYPE_SINGLE_THREADED;
+        use windows::Win32::Graphics::Direct2D::D2D1_RENDER_TARGET_PROPERTIES;
+        use windows::Win32::Graphics::Direct2D::D2D1_RENDER_TARGET_TYPE_SOFTWARE;
+        use windows::Win32::Graphics::Dxgi::Common::DXGI_FORMAT_B8G8R8A8_UNORM;
+
+        unsafe {
+            let factory: ID2D1Factory =
+                D2D1CreateFactory(D2D1_FACTORY_TYPE_SINGLE_THREADED, None).unwrap();
+            let properties = D2D1_RENDER_TARGET_PROPERTIES {
+                r#type: D2D1_RENDER_TARGET_TYPE_SOFTWARE,
+                pixelFormat: D2D1_PIXEL_FORMAT {
+                    format: DXGI_FORMAT_B8G8R8A8_UNORM,
+                    alphaMode: D2D1_ALPHA_MODE_PREMULTIPLIED,
+                },
+                ..Default::default()
+            };
+
+            factory
+                .CreateDCRenderTarget(&properties)
+                .unwrap()
+                .cast()
+                .unwrap()
+        }
+    }
+
+    pub(crate) fn window_rect() -> RECT {
+        RECT {
+            left: 0,
+            top: 0,
+            right: 200,
+            bottom: 100,
+        }
+    }
+
+    #[cfg(windows)]
+    pub(crate) fn brush_properties() -> D2D1_BRUSH_PROPERTIES {
+        D2D1_BRUSH_PROPERTIES {
+            opacity: 1.0,
+            transform: Matrix3x2::identity(),
+        }
+    }
+
+    /// Parses a color and creates its brush on a fresh render target.
+    #[cfg(windows)]
+    pub(crate) fn color_with_brush(s: &str) -> Color {
+        let mut color = parse_color_string(s, None).unwrap();
+        color
+            .to_d2d1_brush(&render_target(), &window_rect(), &brush_properties(), false)
+            .unwrap();
+        color
+    }
+
+    #[test]
+    fn radial_gradient_is_centered_in_window() {
+        let color = parse_color_string("radial-gradient(#ff0000, #0000ff)", None).unwrap();
+        let (center, end) = color.as_gradient().unwrap().pixel_points(&window_rect());
+
+        assert_eq!(center, D2D_POINT_2F { x: 100.0, y: 50.0 });
+        assert_eq!(end, D2D_POINT_2F { x: 200.0, y: 100.0 });
+    }
+
+    #[test]
+    fn selects_colors_by_active_state() {
+        let mapping = ColorMapping {
+            inactive_colors: Some(vec!["#333333".to_string(), "#444444".to_string()]),
+            ..ColorMapping::new(&["#ff0000", "#0000ff"], GradientDirection::from("to right"))
+        };
+        let global_color = GlobalColor::Mapping(mapping);
+        let first_stop = |is_active| {
+            let color = Color::from_global_color(&global_color, is_active).unwrap();
+            color.as_gradient().unwrap().gradient_stops[0].color
+        };
+
+        assert_eq!(first_stop(Some(true)).r, 1.0);
+        assert_eq!(first_stop(None).r, 1.0);
+        assert_eq!(first_stop(Some(false)).r, 0x33 as f32 / 255.0);
+    }
+
+    #[test]
+    fn falls_back_to_active_colors_when_inactive_are_missing() {
+        let global_color = GlobalColor::String("#ff0000".to_string());
+
+        for is_active in [Some(true), Some(false), None] {
+            let color = global_color.to_color(is_active).unwrap();
+            assert_eq!(color.as_solid().unwrap().color.r, 1.0);
+        }
+    }
+
+    fn assert_display_round_trips(s: &str) {
+        // Solids are compared as drawn, since a parsed alpha is kept in the opacity.
+        let drawn = |color: &Color| match color {
+            Color::Solid(solid) => Color::from(solid.effective_color()),
+            color => color.clone(),
+        };
+        let color = parse_color_string(s, None).unwrap();
+        let displayed = color.to_string();
+        let reparsed = parse_color_string(&displayed, None).unwrap();
+
+        assert!(
+            drawn(&color).approx_eq(&drawn(&reparsed), 1e-4),
+            "{} was displayed as {}, which parses to {:?}",
+            s,
+            displayed,
+            reparsed
+        );
+    }
+
+    #[test]
+    fn display_round_trips_solid_colors() {
+        assert_eq!(
+            parse_color_string("#FF8000", None).unwrap().to_string(),
+            "#ff8000"
+        );
+        assert_eq!(
+            parse_color_string("#89b4facc", None).unwrap().to_string(),
+            "#89b4facc"
+        );
+
+        for s in ["#ff8000", "#89b4facc", "rgb(0, 128, 255)", "transparent"] {
+            assert_display_round_trips(s);
+        }
+    }
+
+    #[test]
+    fn display_multiplies_opacity_into_alpha() {
+        let solid = Color::Solid(Solid {
+            opacity: 0.5,
+            ..Solid::from(D2D1_COLOR_F {
+                r: 1.0,
+                g: 0.0,
+                b: 0.0,
+                a: 1.0,
+            })
+        });
+
+        assert_eq!(solid.to_string(), "#ff000080");
+    }
+
+    #[test]
+    fn display_round_trips_gradients() {
+        for s in [
+            "linear-gradient(to right, #ff0000, #00ff00 25%, #0000ff)",
+            "linear-gradient(45deg, #ff0000 10%, #0000ff 90%)",
+            "linear-gradient(#ff000080, #0000ff)",
+            "repeating-linear-gradient(to bottom, #ff0000, #0000ff 20%)",
+            "radial-gradient(circle, #ff0000, #0000ff 50%)",
+            "conic-gradient(from 90deg, #ff0000, #00ff00, #0000ff)",
+        ] {
+            assert_display_round_trips(s);
+        }
+    }
+
+    #[test]
+    fn display_uses_shape_specific_syntax() {
+        let displayed = |s| parse_color_string(s, None).unwrap().to_string();
+
+        assert_eq!(
+            displayed("linear-gradient(to right, #ff0000, #0000ff 50%)"),
+            "linear-gradient(to right, #ff0000 0%, #0000ff 50%)"
+        );
+        assert_eq!(
+            displayed("repeating-radial-gradient(#ff0000, #0000ff 25%)"),
+            "repeating-radial-gradient(#ff0000 0%, #0000ff 25%)"
+        );
+        assert_eq!(
+            displayed("conic-gradient(from 90deg, #ff0000, #0000ff)"),
+            "conic-gradient(from 90deg, #ff0000 0%, #0000ff 100%)"
+        );
+    }
+
+    #[test]
+    fn darken_moves_every_stop_toward_black() {
+        let color = parse_color_string(
+            "linear-gradient(45deg, #ff8080, #80ff80 30%, #8080ff)",
+            None,
+        )
+        .unwrap();
+        let darkened = color.darken(20.0);
+        let (before, after) = (
+            color.as_gradient().unwrap(),
+            darkened.as_gradient().unwrap(),
+        );
+
+        assert_eq!(after.direction, before.direction);
+        assert_eq!(after.gradient_stops.len(), before.gradient_stops.len());
+        for (before, after) in before.gradient_stops.iter().zip(&after.gradient_stops) {
+            assert_eq!(after.position, before.position);
+            assert!(after.color.r <= before.color.r);
+            assert!(after.color.g <= before.color.g);
+            assert!(after.color.b <= before.color.b);
+            assert!(
+                after.color.r + after.color.g + after.color.b
+                    < before.color.r + before.color.g + before.color.b
+            );
+        }
+    }
+
+    #[test]
+    fn lighten_moves_a_solid_toward_white() {
+        let color = parse_color_string("#804020", None).unwrap();
+        let (before, after) = (
+            color.as_solid().unwrap().color,
+            color.lighten(20.0).as_solid().unwrap().color,
+        );
+
+        assert!(after.r > before.r && after.g > before.g && after.b > before.b);
+        assert!(color.get_brush().is_none());
+    }
+
+    #[test]
+    fn global_color_string_serializes_as_bare_string() {
+        let color = GlobalColor::String("#ff0000".to_string());
+        let json = serde_json::to_string(&color).unwrap();
+
+        assert_eq!(json, r##""#ff0000""##);
+        assert_eq!(serde_json::from_str::<GlobalColor>(&json).unwrap(), color);
+    }
+
+    #[test]
+    fn global_color_mapping_serializes_as_object() {
+        let color = GlobalColor::Mapping(ColorMapping {
+            positions: Some(vec![0.0, 0.8]),
+            opacity: Some(0.5),
+            ..ColorMapping::new(
+                &["#ff0000", "#0000ff"],
+                GradientDirection::Coordinates(GradientCoordinates {
+                    start: [0.0, 0.0],
+                    end: [1.0, 1.0],
+                }),
+            )
+        });
+        let json = serde_json::to_value(&color).unwrap();
+
+        assert!(json.is_object());
+        assert_eq!(json["colors"], serde_json::json!(["#ff0000", "#0000ff"]));
+        assert_eq!(serde_json::from_value::<GlobalColor>(json).unwrap(), color);
+    }
+
+    #[test]
+    fn global_color_deserializes_from_integer() {
+        let color: GlobalColor = serde_json::from_str("16734003").unwrap();
+
+        assert_eq!(color, GlobalColor::String("#ff5733".to_string()));
+
+        let solid = color.to_color(None).unwrap();
+        assert_eq!(
+            solid.as_solid().unwrap().to_rgba8(),
+            (0xff, 0x57, 0x33, 0xff)
+        );
+    }
+
+    #[test]
+    fn global_color_rejects_integers_beyond_rgb() {
+        assert!(serde_json::from_str::<GlobalColor>("16777216").is_err());
+        assert!(serde_json::from_str::<GlobalColor>("-1").is_err());
+    }
+
+    #[test]
+    fn global_color_parse_detects_strings() {
+        for input in [
+            "#89b4fa",
+            " accent ",
+            "linear-gradient(90deg, #ff0000, #0000ff)",
+        ] {
+            assert_eq!(
+                GlobalColor::parse(input).unwrap(),
+                GlobalColor::String(input.trim().to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn global_color_parse_detects_mappings() {
+        let color = GlobalColor::parse(
+            r##"{ "colors": ["#ff0000", "#0000ff"], "direction": "to right" }"##,
+        )
+        .unwrap();
+
+        assert_eq!(
+            color,
+            GlobalColor::Mapping(ColorMapping::new(
+                &["#ff0000", "#0000ff"],
+                GradientDirection::from("to right")
+            ))
+        );
+        assert_eq!(
+            GlobalColor::parse(r##"{ "colors": "#ff0000" }"##)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidInput
+        );
+    }
+
+    #[test]
+    fn global_color_round_trips_to_its_canonical_form() {
+        for mapping in [
+            ColorMapping::new(
+                &["#F00", "rgb(0, 0, 255)"],
+                GradientDirection::from("90deg"),
+            ),
+            ColorMapping {
+                positions: Some(vec![0.0, 0.2, 1.0]),
+                opacity: Some(0.5),
+                ..ColorMapping::new(
+                    &["#fff", "#00000080", "#fff"],
+                    GradientDirection::from("45deg"),
+                )
+            },
+            ColorMapping {
+                positions: Some(vec![0.0, 0.5, 1.0]),
+                opacity: Some(1.0),
+                ..ColorMapping::new(
+                    &["#ff0000", "#00ff00", "#0000ff"],
+                    GradientDirection::from("to bottom"),
+                )
+            },
+        ] {
+            let round_tripped = GlobalColor::Mapping(mapping.clone())
+                .to_color(None)
+                .unwrap()
+                .to_global_color()
+                .unwrap();
+
+            assert_eq!(
+                round_tripped,
+                GlobalColor::Mapping(mapping.canonicalize().unwrap())
+            );
+        }
+    }
+
+    #[test]
+    fn solid_converts_to_a_hex_string() {
+        let color = parse_color_string("rgba(255, 0, 0, 0.5)", None).unwrap();
+
+        assert_eq!(
+            color.to_global_color().unwrap(),
+            GlobalColor::String("#ff000080".to_string())
+        );
+        assert_eq!(
+            Color::none().to_global_color().unwrap(),
+            GlobalColor::String("none".to_string())
+        );
+    }
+
+    #[test]
+    fn solid_opacity_is_kept_in_the_hex_alpha() {
+        let color = Color::Solid(Solid {
+            opacity: 0.5,
+            ..Solid::from(D2D1_COLOR_F {
+                r: 1.0,
+                g: 0.0,
+                b: 0.0,
+                a: 1.0,
+            })
+        });
+        let global_color = color.to_global_color().unwrap();
+
+        assert_eq!(global_color, GlobalColor::String("#ff000080".to_string()));
+        assert_eq!(
+            global_color.to_color(None).unwrap().to_string(),
+            color.to_string()
+        );
+    }
+
+    #[test]
+    fn unrepresentable_gradients_fail_to_convert() {
+        for s in [
+            "radial-gradient(#ff0000, #0000ff)",
+            "conic-gradient(#ff0000, #0000ff)",
+            "repeating-linear-gradient(#ff0000, #0000ff 20%)",
+        ] {
+            let color = parse_color_string(s, None).unwrap();
+            let error = color.to_global_color().unwrap_err();
+
+            assert_eq!(error.kind(), ErrorKind::InvalidData, "{}", s);
+        }
+
+        let mut color = parse_color_string("linear-gradient(#ff0000, #0000ff)", None).unwrap();
+        color.as_gradient_mut().unwrap().gamma = GradientGamma::Linear;
+        assert_eq!(
+            color.to_global_color().unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+
+        color.as_gradient_mut().unwrap().gamma = GradientGamma::Srgb;
+        color.set_transform(&Matrix3x2::identity());
+        assert_eq!(
+            color.to_global_color().unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+    }
+
+    #[test]
+    fn converts_from_d2d1_color() {
+        let color = D2D1_COLOR_F {
+            r: 0.25,
+            g: 0.5,
+            b: 0.75,
+            a: 1.0,
+        };
+        let converted = Color::from(color);
+        let solid = converted.as_solid().unwrap();
+
+        assert_eq!(solid.color, color);
+        assert!(solid.brush.is_none());
+    }
+
+    #[test]
+    fn converts_from_clamped_tuple() {
+        let color: Color = (1.0, 0.0, 0.0, 1.0).into();
+        assert_eq!(
+            color.as_solid().unwrap().color,
+            D2D1_COLOR_F {
+                r: 1.0,
+                g: 0.0,
+                b: 0.0,
+                a: 1.0
+            }
+        );
+
+        let color: Color = (1.5, -0.5, 0.5, 2.0).into();
+        assert_eq!(
+            color.as_solid().unwrap().color,
+            D2D1_COLOR_F {
+                r: 1.0,
+                g: 0.0,
+                b: 0.5,
+                a: 1.0
+            }
+        );
+    }
+
+    #[test]
+    fn transparent_is_a_transparent_solid() {
+        let color = Color::transparent();
+        let solid = color.as_solid().unwrap();
+
+        assert_eq!(solid.color, D2D1_COLOR_F::default());
+        assert!(solid.brush.is_none());
+        assert!(color.is_transparent());
+        assert!(!color.is_none());
+    }
+
+    #[test]
+    fn is_transparent_checks_every_stop() {
+        let transparent =
+            parse_color_string("linear-gradient(#ff000000, #0000ff00)", None).unwrap();
+        let partly_visible =
+            parse_color_string("linear-gradient(#ff000000, #0000ff01)", None).unwrap();
+
+        assert!(transparent.is_transparent());
+        assert!(!partly_visible.is_transparent());
+        assert!(!parse_color_string("#ff0000", None)
+            .unwrap()
+            .is_transparent());
+    }
+
+    #[test]
+    fn reversed_leaves_solids_unchanged() {
+        let color = parse_color_string("#ff8000", None).unwrap();
+
+        assert_eq!(color.reversed(), color);
+    }
+
+    #[test]
+    fn parsed_alpha_moves_into_opacity() {
+        for s in [
+            "#ff000080",
+            "#f008",
+            "rgba(255, 0, 0, 0.5)",
+            "hsl(0 100% 50% / 50%)",
+        ] {
+            let color = parse_color_string(s, None).unwrap();
+            let solid = color.as_solid().unwrap();
+
+            assert_eq!(solid.color.a, 1.0, "{}", s);
+            assert!((solid.opacity - 0.5).abs() < 0.05, "{}", s);
+            assert_eq!(solid.effective_color().a, solid.opacity, "{}", s);
+        }
+
+        let opaque = parse_color_string("#ff0000", None).unwrap();
+        assert_eq!(opaque.as_solid().unwrap().opacity, 1.0);
+    }
+
+    #[test]
+    fn mapping_opacity_multiplies_parsed_alpha() {
+        let mapping = ColorMapping {
+            opacity: Some(0.5),
+            ..ColorMapping::new(&["#ff000080"], GradientDirection::from("to right"))
+        };
+        let color = parse_color_mapping(mapping, None).unwrap();
+        let solid = color.as_solid().unwrap();
+
+        assert_eq!(solid.color.a, 1.0);
+        assert!((solid.opacity - 0x80 as f32 / 255.0 * 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn lerp_interpolates_solid_opacity() {
+        let from = Color::Solid(Solid {
+            opacity: 0.2,
+            ..Solid::from(D2D1_COLOR_F::default())
+        });
+        let to = Color::Solid(Solid::from(D2D1_COLOR_F::default()));
+
+        assert_eq!(
+            from.lerp(&to, 0.5).unwrap().as_solid().unwrap().opacity,
+            0.6
+        );
+    }
+
+    #[test]
+    fn approx_eq_tolerates_hsla_round_trips() {
+        use crate::utils::d2d1_to_hsla;
+        use crate::utils::hsla_to_d2d1;
+
+        let original = D2D1_COLOR_F {
+            r: 0.1,
+            g: 0.7,
+            b: 0.3,
+            a: 1.0,
+        };
+        let round_tripped = Color::from(hsla_to_d2d1(d2d1_to_hsla(original)));
+        let original = Color::from(original);
+
+        assert_ne!(original, round_tripped);
+        assert!(original.approx_eq(&round_tripped, 1e-5));
+        assert!(!original.approx_eq(&original.darken(10.0), 1e-5));
+    }
+
+    #[test]
+    fn approx_eq_rejects_mismatched_variants() {
+        let solid = parse_color_string("#ff0000", None).unwrap();
+        let gradient = Color::Gradient(gradient::Gradient::new(
+            GradientCoordinates {
+                start: [0.0, 0.0],
+                end: [1.0, 0.0],
+            },
+            Vec::new(),
+            GradientShape::Linear,
+        ));
+
+        assert!(!solid.approx_eq(&gradient, 1.0));
+        assert!(!gradient.approx_eq(&solid, 1.0));
+    }
+
+    #[test]
+    fn solid_fallback_passes_solids_through() {
+        let color = Color::Solid(Solid {
+            opacity: 0.5,
+            ..Solid::from(D2D1_COLOR_F {
+                r: 0.2,
+                g: 0.4,
+                b: 0.6,
+                a: 1.0,
+            })
+        });
+        let fallback = color.to_solid_fallback();
+
+        assert_eq!(Some(&fallback), color.as_solid());
+    }
+
+    #[test]
+    fn solid_fallback_of_gradient_is_its_midpoint() {
+        let color = parse_color_string("linear-gradient(90deg, #ff0000, #0000ff)", None).unwrap();
+        let fallback = color.to_solid_fallback().color;
+
+        assert!((fallback.r - 0.5).abs() < 1e-6);
+        assert_eq!(fallback.g, 0.0);
+        assert!((fallback.b - 0.5).abs() < 1e-6);
+        assert_eq!(fallback.a, 1.0);
+    }
+
+    fn key_hash(key: &ColorKey) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn approximately_equal_colors_share_a_key() {
+        let color = |r| {
+            ColorKey(Color::from(D2D1_COLOR_F {
+                r,
+                g: 0.4,
+                b: 0.6,
+                a: 1.0,
+            }))
+        };
+        let (a, b) = (color(0.2), color(0.2 + 1e-7));
+
+        assert_ne!(a.0, b.0);
+        assert_eq!(a, b);
+        assert_eq!(key_hash(&a), key_hash(&b));
+        assert_ne!(a, color(0.3));
+    }
+
+    #[test]
+    fn gradient_keys_cover_stops_and_shape() {
+        let key = |s| ColorKey(parse_color_string(s, None).unwrap());
+        let linear = key("linear-gradient(90deg, #ff0000, #0000ff)");
+
+        assert_eq!(linear, key("linear-gradient(90deg, #ff0000, #0000ff)"));
+        assert_ne!(linear, key("linear-gradient(90deg, #ff0000, #0000ff 80%)"));
+        assert_ne!(linear, key("radial-gradient(#ff0000, #0000ff)"));
+        assert_ne!(linear, key("#ff0000"));
+    }
+
+    #[test]
+    fn accessors_match_the_variant() {
+        let mut solid = parse_color_string("#ff0000", None).unwrap();
+        let mut gradient =
+            parse_color_string("linear-gradient(90deg, #ff0000, #0000ff)", None).unwrap();
+
+        assert!(solid.is_solid() && !solid.is_gradient());
+        assert!(solid.as_solid().is_some() && solid.as_gradient().is_none());
+        assert!(solid.as_gradient_mut().is_none());
+        solid.as_solid_mut().unwrap().opacity = 0.5;
+        assert_eq!(solid.as_solid().unwrap().opacity, 0.5);
+
+        assert!(gradient.is_gradient() && !gradient.is_solid());
+        assert!(gradient.as_gradient().is_some() && gradient.as_solid().is_none());
+        assert!(gradient.as_solid_mut().is_none());
+        gradient.as_gradient_mut().unwrap().opacity = 0.5;
+        assert_eq!(gradient.as_gradient().unwrap().opacity, 0.5);
+    }
+
+    #[test]
+    fn equality_ignores_the_cached_brush_key() {
+        for s in ["#ff0000", "linear-gradient(90deg, #ff0000, #0000ff)"] {
+            let color = parse_color_string(s, None).unwrap();
+            let mut cached = color.clone();
+            match cached {
+                Color::Solid(ref mut solid) => solid.brush_key = Some(1),
+                Color::Gradient(ref mut gradient) => gradient.brush_key = Some(1),
+            }
+
+            assert_eq!(color, cached, "{}", s);
+        }
+    }
+
+    #[test]
+    fn resolve_first_skips_invalid_candidates() {
+        let candidates = [
+            GlobalColor::String("#gg0000".to_string()),
+            GlobalColor::String("#00ff00".to_string()),
+            GlobalColor::String("#0000ff".to_string()),
+        ];
+        let color = resolve_first(&candidates, None).unwrap();
+
+        assert_eq!(color, parse_color_string("#00ff00", None).unwrap());
+    }
+
+    #[test]
+    fn resolve_first_lists_every_failure() {
+        let candidates = [
+            GlobalColor::String("#gg0000".to_string()),
+            GlobalColor::String("rgb(300, 0, 0)".to_string()),
+        ];
+        let error = resolve_first(&candidates, None).unwrap_err();
+        let message = error.to_string();
+
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        assert!(
+            message.contains("candidate 0") && message.contains("candidate 1"),
+            "{}",
+            message
+        );
+        assert_eq!(
+            resolve_first(&[], None).unwrap_err().kind(),
+            ErrorKind::InvalidInput
+        );
+    }
+
+    #[test]
+    fn uniform_gradient_simplifies_to_a_solid() {
+        let mut color =
+            parse_color_string("linear-gradient(#ff0000, #ff0000 40%, #ff0000)", None).unwrap();
+        color.as_gradient_mut().unwrap().opacity = 0.5;
+
+        let simplified = color.simplify();
+        let solid = simplified.as_solid().unwrap();
+
+        assert_eq!(
+            solid.color,
+            parse_color_string("#ff0000", None)
+                .unwrap()
+                .as_solid()
+                .unwrap()
+                .color
+        );
+        assert_eq!(solid.opacity, 0.5);
+        assert!(solid.brush.is_none());
+    }
+
+    #[test]
+    fn real_gradient_stays_a_gradient() {
+        let color = parse_color_string("linear-gradient(#ff0000, #0000ff)", None).unwrap();
+
+        assert_eq!(color.clone().simplify(), color);
+
+        let solid = parse_color_string("#00ff00", None).unwrap();
+        assert_eq!(solid.clone().simplify(), solid);
+    }
+
+    #[test]
+    fn default_color_can_be_configured() {
+        // This is the only test that touches the process-wide default, and it resets it.
+        let reset = || *default_color().write().unwrap() = None;
+        reset();
+
+        assert_eq!(
+            GlobalColor::default(),
+            GlobalColor::String("#000000".to_string())
+        );
+        assert_eq!(Color::default(), Color::from(D2D1_COLOR_F::default()));
+
+        set_default_color(GlobalColor::String("#1e1e2e".to_string()));
+
+        assert_eq!(
+            GlobalColor::default(),
+            GlobalColor::String("#1e1e2e".to_string())
+        );
+        assert_eq!(
+            Color::default(),
+            parse_color_string("#1e1e2e", None).unwrap()
+        );
+
+        // A default that fails to convert falls back to transparent black.
+        set_default_color(GlobalColor::String("#gg0000".to_string()));
+
+        assert_eq!(Color::default(), Color::from(D2D1_COLOR_F::default()));
+
+        reset();
+    }
+
+    #[test]
+    fn rotate_hue_cycles_the_primaries() {
+        let rgb = |r, g, b| D2D1_COLOR_F { r, g, b, a: 1.0 };
+        let assert_rotates_to = |color: &Color, expected: D2D1_COLOR_F| {
+            let actual = color.as_solid().unwrap().color;
+            for (a, b) in [
+                (actual.r, expected.r),
+                (actual.g, expected.g),
+                (actual.b, expected.b),
+            ] {
+                assert!((a - b).abs() < 1e-5, "{:?} != {:?}", actual, expected);
+            }
+            assert_eq!(actual.a, expected.a);
+        };
+        let red = Color::from(rgb(1.0, 0.0, 0.0));
+
+        assert_rotates_to(&red.rotate_hue(120.0), rgb(0.0, 1.0, 0.0));
+        assert_rotates_to(&red.rotate_hue(240.0), rgb(0.0, 0.0, 1.0));
+        assert_rotates_to(&red.rotate_hue(480.0), rgb(0.0, 1.0, 0.0));
+        assert_rotates_to(&red.rotate_hue(-120.0), rgb(0.0, 0.0, 1.0));
+        assert_rotates_to(&red.rotate_hue(360.0), rgb(1.0, 0.0, 0.0));
+
+        let gray = Color::from(rgb(0.5, 0.5, 0.5));
+        assert_rotates_to(&gray.rotate_hue(90.0), rgb(0.5, 0.5, 0.5));
+    }
+
+    #[test]
+    fn rotate_hue_rotates_every_gradient_stop() {
+        let color =
+            parse_color_string("linear-gradient(#ff0000, #00ff00 30%, #0000ff)", None).unwrap();
+        let rotated = color.rotate_hue(120.0);
+        let (original, rotated) = (color.as_gradient().unwrap(), rotated.as_gradient().unwrap());
+
+        assert_eq!(rotated.direction, original.direction);
+        assert_eq!(rotated.gradient_stops.len(), 3);
+        for (before, after) in original.gradient_stops.iter().zip(&rotated.gradient_stops) {
+            let expected = Color::from(before.color).rotate_hue(120.0);
+
+            assert_eq!(after.position, before.position);
+            assert_eq!(after.color, expected.as_solid().unwrap().color);
+        }
+        // Red, green and blue each move one primary along.
+        assert!(rotated.gradient_stops[0].color.g > 0.99);
+        assert!(rotated.gradient_stops[1].color.b > 0.99);
+        assert!(rotated.gradient_stops[2].color.r > 0.99);
+    }
+
+    #[test]
+    fn none_parses_and_displays_as_none() {
+        for s in ["none", " NONE "] {
+            let color = parse_color_string(s, None).unwrap();
+
+            assert!(color.is_none());
+            assert_eq!(color, Color::none());
+            assert_eq!(color.to_string(), "none");
+            assert!(parse_color_string(&color.to_string(), None)
+                .unwrap()
+                .is_none());
+        }
+
+        let transparent = parse_color_string("transparent", None).unwrap();
+
+        assert!(!transparent.is_none());
+        assert_ne!(transparent.to_string(), "none");
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn creates_radial_gradient_brush() {
+        use windows::Win32::Graphics::Direct2D::ID2D1RadialGradientBrush;
+
+        let mut color =
+            parse_color_string("radial-gradient(circle, #ff0000, #0000ff)", None).unwrap();
+        assert_eq!(color.as_gradient().unwrap().shape, GradientShape::Radial);
+
+        color
+            .to_d2d1_brush(&render_target(), &window_rect(), &brush_properties(), false)
+            .unwrap();
+
+        let brush: ID2D1RadialGradientBrush = color.get_brush().unwrap().cast().unwrap();
+        unsafe {
+            assert_eq!(brush.GetCenter(), D2D_POINT_2F { x: 100.0, y: 50.0 });
+            assert_eq!(brush.GetRadiusX(), 100.0);
+            assert_eq!(brush.GetRadiusY(), 50.0);
+            assert_eq!(
+                brush
+                    .GetGradientStopCollection()
+                    .unwrap()
+                    .GetGradientStopCount(),
+                2
+            );
+        }
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn reuses_brush_across_identical_calls() {
+        let render_target = render_target();
+
+        for s in ["#ff0000", "linear-gradient(to right, #ff0000, #0000ff)"] {
+            let mut color = parse_color_string(s, None).unwrap();
+            let create_brush = |color: &mut Color| {
+                color
+                    .to_d2d1_brush(&render_target, &window_rect(), &brush_properties(), false)
+                    .unwrap();
+                color.get_brush().unwrap().as_raw()
+            };
+
+            let first = create_brush(&mut color);
+            assert_eq!(create_brush(&mut color), first);
+
+            color.invalidate();
+            assert_ne!(create_brush(&mut color), first);
+        }
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn recreates_brush_for_another_render_target() {
+        let (first_target, second_target) = (render_target(), render_target());
+
+        for s in ["#ff0000", "linear-gradient(to right, #ff0000, #0000ff)"] {
+            let mut color = parse_color_string(s, None).unwrap();
+            let mut create_brush = |render_target: &ID2D1RenderTarget| {
+                color
+                    .to_d2d1_brush(render_target, &window_rect(), &brush_properties(), false)
+                    .unwrap();
+                let brush_target = match &color {
+                    Color::Solid(solid) => solid.brush_target.clone(),
+                    Color::Gradient(gradient) => gradient.brush_target.clone(),
+                };
+                assert!(is_same_render_target(brush_target.as_ref(), render_target));
+                color.get_brush().unwrap().as_raw()
+            };
+
+            let first = create_brush(&first_target);
+            assert_ne!(create_brush(&second_target), first);
+        }
+
+        assert!(!is_same_render_target(Some(&first_target), &second_target));
+        assert!(!is_same_render_target(None, &first_target));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn editing_gradient_stops_clears_the_brush() {
+        let edits: [fn(&mut Gradient); 2] = [
+            |gradient| gradient.reverse(),
+            |gradient| gradient.remap_positions(0.25, 0.75).unwrap(),
+        ];
+
+        for edit in edits {
+            let mut color = color_with_brush("linear-gradient(to right, #ff0000, #0000ff)");
+            let Color::Gradient(ref mut gradient) = color else {
+                panic!("expected a gradient");
+            };
+            edit(gradient);
+
+            assert!(gradient.brush.is_none());
+            assert_eq!(gradient.brush_key, None);
+            assert!(gradient.brush_target.is_none());
+        }
+    }
+
+    #[test]
+    fn colorref_of_gradient_is_first_stop() {
+        let solid = parse_color_string("#336699", None).unwrap();
+        let gradient = parse_color_string("linear-gradient(#336699, #ffffff)", None).unwrap();
+
+        assert_eq!(solid.to_colorref(), Some(0x00996633));
+        assert_eq!(gradient.to_colorref(), Some(0x00996633));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn multiply_opacity_scales_current_opacity() {
+        for s in ["#ff0000", "linear-gradient(#ff000080, #0000ff)"] {
+            let color = color_with_brush(s);
+
+            color.set_opacity(0.8);
+            color.multiply_opacity(0.5);
+            assert!((color.get_opacity().unwrap() - 0.4).abs() < 1e-6);
+
+            color.multiply_opacity(4.0);
+            assert_eq!(color.get_opacity(), Some(1.0));
+        }
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn invalid_color_fails_before_creating_a_brush() {
+        let mut color = Color::from(D2D1_COLOR_F {
+            r: f32::NAN,
+            g: 0.0,
+            b: 0.0,
+            a: 1.0,
+        });
+        let error = color
+            .to_d2d1_brush(&render_target(), &window_rect(), &brush_properties(), false)
+            .unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        assert!(color.get_brush().is_none());
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn solid_opacity_applies_to_new_brushes() {
+        let mut color = Color::Solid(Solid {
+            opacity: 0.5,
+            ..Solid::from(D2D1_COLOR_F {
+                r: 1.0,
+                g: 0.0,
+                b: 0.0,
+                a: 1.0,
+            })
+        });
+        color
+            .to_d2d1_brush(&render_target(), &window_rect(), &brush_properties(), false)
+            .unwrap();
+        assert_eq!(color.get_opacity(), Some(0.5));
+
+        color.set_opacity(1.0);
+        assert_eq!(color.get_opacity(), Some(1.0));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn new_brushes_are_visible_unless_initially_hidden() {
+        for (initially_hidden, expected) in [(false, 1.0), (true, 0.0)] {
+            let mut color = parse_color_string("#89b4fa", None).unwrap();
+            color
+                .to_d2d1_brush(
+                    &render_target(),
+                    &window_rect(),
+                    &brush_properties(),
+                    initially_hidden,
+                )
+                .unwrap();
+
+            assert_eq!(color.get_opacity(), Some(expected));
+        }
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn on_resize_moves_gradient_points() {
+        use windows::Win32::Graphics::Direct2D::ID2D1LinearGradientBrush;
+
+        let color = color_with_brush("linear-gradient(to right, #ff0000, #0000ff)");
+        let brush: ID2D1LinearGradientBrush = color.get_brush().unwrap().cast().unwrap();
+        unsafe {
+            assert_eq!(brush.GetEndPoint(), D2D_POINT_2F { x: 200.0, y: 50.0 });
+        }
+
+        color.on_resize(&RECT {
+            left: 0,
+            top: 0,
+            right: 400,
+            bottom: 300,
+        });
+        unsafe {
+            assert_eq!(brush.GetStartPoint(), D2D_POINT_2F { x: 0.0, y: 150.0 });
+            assert_eq!(brush.GetEndPoint(), D2D_POINT_2F { x: 400.0, y: 150.0 });
+        }
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn creates_brush_for_each_extend_mode() {
+        use windows::Win32::Graphics::Direct2D::Common::D2D1_GRADIENT_STOP;
+        use windows::Win32::Graphics::Direct2D::ID2D1LinearGradientBrush;
+
+        for extend_mode in [
+            GradientExtendMode::Clamp,
+            GradientExtendMode::Wrap,
+            GradientExtendMode::Mirror,
+        ] {
+            let mut color =
+                parse_color_string("linear-gradient(to right, #ff0000, #0000ff 25%)", None)
+                    .unwrap();
+            color.as_gradient_mut().unwrap().extend_mode = extend_mode;
+            color
+                .to_d2d1_brush(&render_target(), &window_rect(), &brush_properties(), false)
+                .unwrap();
+
+            let brush: ID2D1LinearGradientBrush = color.get_brush().unwrap().cast().unwrap();
+            unsafe {
+                let collection = brush.GetGradientStopCollection().unwrap();
+                let mut stops = [D2D1_GRADIENT_STOP::default(); 2];
+                collection.GetGradientStops(&mut stops);
+
+                assert_eq!(collection.GetExtendMode(), extend_mode.into());
+                match extend_mode {
+                    GradientExtendMode::Clamp => {
+                        assert_eq!(brush.GetEndPoint().x, 200.0);
+                        assert_eq!(stops[1].position, 0.25);
+                    }
+                    _ => {
+                        assert_eq!(brush.GetEndPoint().x, 50.0);
+                        assert_eq!(stops[1].position, 1.0);
+                    }
+                }
+            }
+        }
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn creates_brush_with_selected_gamma() {
+        use windows::Win32::Graphics::Direct2D::ID2D1LinearGradientBrush;
+
+        for gamma in [GradientGamma::Srgb, GradientGamma::Linear] {
+            let mut color =
+                parse_color_string("linear-gradient(to right, #ff0000, #0000ff)", None).unwrap();
+            color.as_gradient_mut().unwrap().gamma = gamma;
+            color
+                .to_d2d1_brush(&render_target(), &window_rect(), &brush_properties(), false)
+                .unwrap();
+
+            let brush: ID2D1LinearGradientBrush = color.get_brush().unwrap().cast().unwrap();
+            unsafe {
+                assert_eq!(
+                    brush
+                        .GetGradientStopCollection()
+                        .unwrap()
+                        .GetColorInterpolationGamma(),
+                    gamma.into()
+                );
+            }
+        }
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn creates_brush_on_device_context() {
+        use windows::Win32::Foundation::HMODULE;
+        use windows::Win32::Graphics::Direct2D::D2D1CreateFactory;
+        use windows::Win32::Graphics::Direct2D::ID2D1DeviceContext;
+        use windows::Win32::Graphics::Direct2D::ID2D1Factory1;
+        use windows::Win32::Graphics::Direct2D::D2D1_DEVICE_CONTEXT_OPTIONS_NONE;
+        use windows::Win32::Graphics::Direct2D::D2D1_FACTORY_TYPE_SINGLE_THREADED;
+        use windows::Win32::Graphics::Direct3D::D3D_DRIVER_TYPE_WARP;
+        use windows::Win32::Graphics::Direct3D11::D3D11CreateDevice;
+        use windows::Win32::Graphics::Direct3D11::D3D11_CREATE_DEVICE_BGRA_SUPPORT;
+        use windows::Win32::Graphics::Direct3D11::D3D11_SDK_VERSION;
+        use windows::Win32::Graphics::Dxgi::IDXGIDevice;
+
+        let device_context: ID2D1DeviceContext = unsafe {
+            let mut device = None;
+            D3D11CreateDevice(
+                None,
+                D3D_DRIVER_TYPE_WARP,
+                HMODULE::default(),
+                D3D11_CREATE_DEVICE_BGRA_SUPPORT,
+                None,
+                D3D11_SDK_VERSION,
+                Some(&mut device),
+                None,
+                None,
+            )
+            .unwrap();
+            let dxgi_device: IDXGIDevice = device.unwrap().cast().unwrap();
+            let factory: ID2D1Factory1 =
+                D2D1CreateFactory(D2D1_FACTORY_TYPE_SINGLE_THREADED, None).unwrap();
+
+            factory
+                .CreateDevice(&dxgi_device)
+                .unwrap()
+                .CreateDeviceContext(D2D1_DEVICE_CONTEXT_OPTIONS_NONE)
+                .unwrap()
+        };
+
+        for s in ["#ff0000", "linear-gradient(to right, #ff0000, #0000ff)"] {
+            let mut color = parse_color_string(s, None).unwrap();
+            color
+                .to_d2d1_brush(&device_context, &window_rect(), &brush_properties(), false)
+                .unwrap();
+
+            assert!(color.get_brush().is_some(), "{}", s);
+        }
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn preview_of_gradient_is_its_average_color() {
+        let color =
+            parse_color_string("linear-gradient(to right, #ff0000, #0000ff)", None).unwrap();
+        let brush = color
+            .to_preview_solid(&render_target(), &brush_properties())
+            .unwrap();
+        let average = color.as_gradient().unwrap().average_color();
+
+        unsafe {
+            assert_eq!(brush.GetColor(), average);
+        }
+        assert!(color.get_brush().is_none());
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn preview_of_solid_is_the_solid_color() {
+        let color = parse_color_string("#89b4fa", None).unwrap();
+        let brush = color
+            .to_preview_solid(&render_target(), &brush_properties())
+            .unwrap();
+
+        unsafe {
+            assert_eq!(brush.GetColor(), color.as_solid().unwrap().color);
+        }
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn equality_ignores_the_brush() {
+        for s in ["#ff0000", "linear-gradient(to right, #ff0000, #0000ff)"] {
+            let with_brush = color_with_brush(s);
+            let without_brush = parse_color_string(s, None).unwrap();
+
+            assert!(with_brush.get_brush().is_some());
+            assert_eq!(with_brush, without_brush, "{}", s);
+            assert_ne!(with_brush, parse_color_string("#00ff00", None).unwrap());
+        }
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn stored_transform_survives_brush_recreation() {
+        let transform = Matrix3x2::translation(10.0, 20.0);
+
+        for s in ["#ff0000", "linear-gradient(to right, #ff0000, #0000ff)"] {
+            let mut color = color_with_brush(s);
+            color.set_transform(&transform);
+            let brush = color.get_brush().unwrap().clone();
+
+            color.invalidate();
+            color
+                .to_d2d1_brush(&render_target(), &window_rect(), &brush_properties(), false)
+                .unwrap();
+
+            let recreated = color.get_brush().unwrap();
+            assert_ne!(recreated.as_raw(), brush.as_raw(), "{}", s);
+
+            let mut applied = Matrix3x2::default();
+            unsafe { recreated.GetTransform(&mut applied) };
+            assert_eq!(applied, transform, "{}", s);
+        }
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn create_brush_returns_a_brush_without_storing_it() {
+        use windows::Win32::Graphics::Direct2D::ID2D1LinearGradientBrush;
+        use windows::Win32::Graphics::Direct2D::ID2D1SolidColorBrush;
+
+        let target = render_target();
+        let solid = parse_color_string("#ff0000", None).unwrap();
+        let brush: ID2D1SolidColorBrush = solid
+            .create_brush(&target, &window_rect(), &brush_properties())
+            .unwrap()
+            .cast()
+            .unwrap();
+
+        unsafe { assert_eq!(brush.GetColor(), solid.as_solid().unwrap().color) };
+        assert!(solid.get_brush().is_none());
+
+        let gradient = parse_color_string("linear-gradient(#ff0000, #0000ff)", None).unwrap();
+        let brush = gradient
+            .create_brush(&target, &window_rect(), &brush_properties())
+            .unwrap();
+
+        assert!(brush.cast::<ID2D1LinearGradientBrush>().is_ok());
+        assert!(gradient.get_brush().is_none());
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn set_opacity_clamps_and_ignores_non_finite_values() {
+        for s in ["#ff0000", "linear-gradient(#ff0000, #0000ff)"] {
+            let color = color_with_brush(s);
+
+            color.set_opacity(2.0);
+            assert_eq!(color.get_opacity(), Some(1.0));
+            color.set_opacity(-0.5);
+            assert_eq!(color.get_opacity(), Some(0.0));
+
+            color.set_opacity(0.5);
+            for value in [f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+                color.set_opacity(value);
+                color.multiply_opacity(value);
+                assert_eq!(color.get_opacity(), Some(0.5));
+            }
+        }
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn try_set_opacity_rejects_invalid_values() {
+        let color = color_with_brush("#ff0000");
+
+        color.try_set_opacity(0.25).unwrap();
+        assert_eq!(color.get_opacity(), Some(0.25));
+
+        for value in [2.0, -0.5, f32::NAN, f32::INFINITY] {
+            let error = color.try_set_opacity(value).unwrap_err();
+
+            assert_eq!(error.kind(), ErrorKind::InvalidInput);
+            assert_eq!(color.get_opacity(), Some(0.25));
+        }
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn rendering_none_creates_no_brush() {
+        let target = render_target();
+        let mut color = Color::none();
+
+        color
+            .to_d2d1_brush(&target, &window_rect(), &brush_properties(), false)
+            .unwrap();
+        assert!(color.get_brush().is_none());
+
+        // A color switched to none releases the brush it had.
+        let mut color = color_with_brush("#ff0000");
+        assert!(color.get_brush().is_some());
+        color.as_solid_mut().unwrap().none = true;
+        color
+            .to_d2d1_brush(&target, &window_rect(), &brush_properties(), false)
+            .unwrap();
+
+        assert!(color.get_brush().is_none());
+        assert_eq!(color.get_opacity(), None);
     }
 }
@@ -0,0 +1,233 @@
+use serde::Deserialize;
+use std::path::Path;
+
+use crate::error::Error;
+use crate::error::ErrorKind;
+use crate::error::Result;
+use crate::parser::parse_color_string;
+use crate::Color;
+
+/// An entry of a JSON palette.
+#[derive(Deserialize)]
+struct PaletteEntry {
+    name: String,
+    value: String,
+}
+
+/// Loads a list of named colors from a palette file.
+///
+/// The format is chosen by the file extension:
+/// - `.gpl`: A GIMP palette, starting with a `GIMP Palette` line and followed by one color per
+///   line as red, green and blue values from 0 to 255 and an optional name. Blank lines, `#`
+///   comments and the `Name:` and `Columns:` headers are skipped. Unnamed colors are named after
+///   their hex value.
+/// - `.json`: An array of `{ "name": ..., "value": ... }` objects, where each value is any color
+///   accepted by `parse_color_string`.
+///
+/// # Parameters
+/// - `path`: The path of the palette file.
+///
+/// # Returns
+/// A `Result` containing the names and colors in file order, an `ErrorKind::InvalidInput` error
+/// if the file cannot be read or has an unsupported extension, or an `ErrorKind::InvalidData`
+/// error naming the offending line or entry if the palette is malformed.
+///
+/// # Example
+/// ```rust,no_run
+/// # use std::path::Path;
+/// # use win_color::load_palette;
+/// # fn main() -> win_color::Result<()> {
+/// let palette = load_palette(Path::new("themes/catppuccin.gpl"))?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn load_palette(path: &Path) -> Result<Vec<(String, Color)>> {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_ascii_lowercase);
+
+    let read = || {
+        std::fs::read_to_string(path).map_err(|e| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("Failed to read the palette {}: {}", path.display(), e),
+            )
+        })
+    };
+
+    match extension.as_deref() {
+        Some("gpl") => parse_gpl_palette(&read()?),
+        Some("json") => parse_json_palette(&read()?),
+        _ => Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("Unsupported palette format: {}", path.display()),
+        )),
+    }
+}
+
+/// Parses the contents of a GIMP `.gpl` palette, as described in `load_palette`.
+fn parse_gpl_palette(contents: &str) -> Result<Vec<(String, Color)>> {
+    let mut lines = contents.lines().enumerate();
+
+    // `Option::is_none_or` would need Rust 1.82.
+    #[allow(clippy::unnecessary_map_or)]
+    let missing_header = lines
+        .next()
+        .map_or(true, |(_, line)| line.trim() != "GIMP Palette");
+
+    if missing_header {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "A GIMP palette must start with a `GIMP Palette` line",
+        ));
+    }
+
+    let mut colors = Vec::new();
+
+    for (i, line) in lines {
+        let trimmed = line.trim();
+        if trimmed.is_empty()
+            || trimmed.starts_with('#')
+            || trimmed.starts_with("Name:")
+            || trimmed.starts_with("Columns:")
+        {
+            continue;
+        }
+
+        let invalid = || {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("Invalid palette line {}: {}", i + 1, line),
+            )
+        };
+
+        let parts: Vec<&str> = trimmed.split_whitespace().collect();
+        let [r, g, b, name @ ..] = parts.as_slice() else {
+            return Err(invalid());
+        };
+
+        let color = parse_color_string(&format!("rgb({}, {}, {})", r, g, b), None)
+            .map_err(|_| invalid())?;
+        let name = match name {
+            [] => color.to_string(),
+            name => name.join(" "),
+        };
+
+        colors.push((name, color));
+    }
+
+    Ok(colors)
+}
+
+/// Parses the contents of a JSON palette, as described in `load_palette`.
+fn parse_json_palette(contents: &str) -> Result<Vec<(String, Color)>> {
+    let entries: Vec<PaletteEntry> = serde_json::from_str(contents).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("Invalid JSON palette: {}", e),
+        )
+    })?;
+
+    entries
+        .into_iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            let color = parse_color_string(&entry.value, None).map_err(|e| {
+                Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "Invalid palette entry {} ({}: {}): {}",
+                        i, entry.name, entry.value, e
+                    ),
+                )
+            })?;
+
+            Ok((entry.name, color))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ColorImpl;
+    use crate::SolidImpl;
+    use std::path::PathBuf;
+
+    /// Writes a palette into the temporary directory and returns its path.
+    fn write_palette(name: &str, contents: &str) -> PathBuf {
+        // The process id keeps concurrent test runs from sharing a file.
+        let path = std::env::temp_dir().join(format!("win-color-{}-{}", std::process::id(), name));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn loads_gpl_palette() {
+        let path = write_palette(
+            "palette.gpl",
+            "GIMP Palette\nName: Sample\nColumns: 2\n# A comment\n\n255   0   0 Bright Red\n  0   0 255\n",
+        );
+        let palette = load_palette(&path).unwrap();
+
+        assert_eq!(palette.len(), 2);
+        assert_eq!(palette[0].0, "Bright Red");
+        assert_eq!(
+            palette[0].1.as_solid().unwrap().to_rgba8(),
+            (255, 0, 0, 255)
+        );
+        assert_eq!(palette[1].0, palette[1].1.to_string());
+        assert_eq!(
+            palette[1].1.as_solid().unwrap().to_rgba8(),
+            (0, 0, 255, 255)
+        );
+    }
+
+    #[test]
+    fn loads_json_palette() {
+        let path = write_palette(
+            "palette.json",
+            r##"[
+                { "name": "red", "value": "#ff0000" },
+                { "name": "fade", "value": "linear-gradient(#ff0000, #0000ff)" }
+            ]"##,
+        );
+        let palette = load_palette(&path).unwrap();
+
+        assert_eq!(palette.len(), 2);
+        assert_eq!(palette[0].0, "red");
+        assert_eq!(
+            palette[0].1.as_solid().unwrap().to_rgba8(),
+            (255, 0, 0, 255)
+        );
+        assert_eq!(palette[1].0, "fade");
+        assert!(palette[1].1.as_gradient().is_some());
+    }
+
+    #[test]
+    fn reports_the_offending_line() {
+        for (contents, line) in [
+            ("GIMP Palette\n255 0 0 Red\n255 0\n", "line 3"),
+            ("GIMP Palette\n# Comment\n255 0 300 Too Bright\n", "line 3"),
+            ("Not a palette\n255 0 0\n", "GIMP Palette"),
+        ] {
+            let error = parse_gpl_palette(contents).unwrap_err();
+
+            assert_eq!(error.kind(), ErrorKind::InvalidData);
+            assert!(error.message().contains(line), "{}", error);
+        }
+
+        let error = parse_json_palette(r##"[{ "name": "bad", "value": "#gg0000" }]"##).unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        assert!(error.message().contains("bad: #gg0000"), "{}", error);
+    }
+
+    #[test]
+    fn rejects_unsupported_extensions() {
+        let error = load_palette(Path::new("palette.txt")).unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+    }
+}
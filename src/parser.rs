@@ -2,64 +2,123 @@
 //! It supports solid colors, gradients, and their mapping to Direct2D structures.
 
 use colorparser_css::Color as CssColor;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use std::sync::RwLock;
+use windows::core::w;
+use windows::core::PCWSTR;
+use windows::Win32::Foundation::BOOL;
 use windows::Win32::Graphics::Direct2D::Common::D2D1_COLOR_F;
 use windows::Win32::Graphics::Direct2D::Common::D2D1_GRADIENT_STOP;
+use windows::Win32::Graphics::Dwm::DwmGetColorizationColor;
+use windows::Win32::System::Registry::RegGetValueW;
+use windows::Win32::System::Registry::HKEY_CURRENT_USER;
+use windows::Win32::System::Registry::RRF_RT_REG_DWORD;
 
 use crate::error::Error;
 use crate::error::ErrorKind;
 use crate::error::Result;
+use crate::gradient::angle_to_coordinates;
+use crate::utils::darken;
+use crate::utils::hsla_to_d2d1;
+use crate::utils::lerp_color;
+use crate::utils::lighten;
+use crate::utils::oklab_to_d2d1;
+use crate::utils::strip_string;
+use crate::utils::Hsla;
 use crate::Color;
+use crate::ColorImpl;
 use crate::ColorMapping;
 use crate::Gradient;
 use crate::GradientCoordinates;
 use crate::GradientDirection;
+use crate::GradientExtendMode;
+use crate::GradientInterpolation;
+use crate::GradientShape;
 use crate::Solid;
+use crate::SolidImpl;
+use crate::EASE_IN_OUT_SEGMENTS;
+
+/// Parses the arguments of a gradient function, such as `linear-gradient(...)`, into a `Color`.
+type GradientParser = fn(&str, Option<bool>) -> Result<Color>;
+
+/// The percentage by which `accent_dark` darkens the system accent color.
+const ACCENT_DARKEN_PERCENTAGE: f32 = 40.0;
+/// The percentage by which `accent_light` lightens the system accent color.
+const ACCENT_LIGHTEN_PERCENTAGE: f32 = 40.0;
 
 /// Parses a `ColorMapping` into a `Color`.
 ///
 /// # Arguments
 ///
 /// - `s`: A `ColorMapping` containing the color definitions to parse.
-/// - `is_active`: An optional flag indicating whether the color mapping is active.
+/// - `is_active`: An optional flag indicating whether the color mapping is active. When it is
+///   `Some(false)` and the mapping defines `inactive_colors`, those are used instead of `colors`.
+///   `None` is treated as active.
 ///
 /// # Returns
 ///
-/// - `Ok(Color)`: A parsed `Color` object, which can be a solid color or a gradient.
-/// - `Err(WinColorError)`: An error if the parsing fails.
+/// - `Ok(Color)`: A parsed `Color` object, which can be a solid color or a gradient. The mapping's
+///   `opacity`, clamped to 0.0 to 1.0, is stored on the color and applied when its brush is
+///   created. For a solid color it multiplies the opacity parsed from the color's alpha.
+/// - `Err(Error)`: An error if the parsing fails.
 ///
 /// # Examples
 ///
-/// ```rust
-/// let mapping = ColorMapping {
-///     colors: vec!["#FF0000".to_string()],
-///     direction: GradientCoordinates::String("90deg".to_string())
-/// };
+/// ```rust,ignore
+/// let mapping = ColorMapping::new(&["#FF0000", "#00FF00"], GradientDirection::from("90deg"));
 /// let color = parse_color_mapping(mapping, Some(false))?;
 /// ```
-pub fn parse_color_mapping(s: ColorMapping) -> Result<Color> {
-    match s.colors.len() {
-        0 => Ok(Color::Solid(Solid {
-            color: D2D1_COLOR_F::default(),
-            brush: None,
-        })),
+pub fn parse_color_mapping(s: ColorMapping, is_active: Option<bool>) -> Result<Color> {
+    let mut color = parse_color_mapping_colors(&s, is_active)?;
+
+    if let Some(opacity) = s.opacity.filter(|opacity| !opacity.is_nan()) {
+        let opacity = opacity.clamp(0.0, 1.0);
+        match color {
+            Color::Solid(ref mut solid) => solid.opacity *= opacity,
+            Color::Gradient(ref mut gradient) => gradient.opacity = opacity,
+        }
+    }
+
+    Ok(color)
+}
+
+/// Parses the colors of a `ColorMapping` into a solid color or gradient, as for
+/// `parse_color_mapping`.
+fn parse_color_mapping_colors(s: &ColorMapping, is_active: Option<bool>) -> Result<Color> {
+    let colors = match (is_active, &s.inactive_colors) {
+        (Some(false), Some(inactive_colors)) => inactive_colors,
+        _ => &s.colors,
+    };
+
+    match colors.len() {
+        0 => Ok(Color::Solid(Solid::from(D2D1_COLOR_F::default()))),
         1 => {
-            let result = parse_color_string(&s.colors[0])?;
+            let result = parse_color_string(&colors[0], is_active)?;
             Ok(result)
         }
         _ => {
-            let gradient_stops = generate_gradient_stops(&s.colors)?;
+            let gradient_stops = generate_gradient_stops(
+                colors,
+                s.positions.as_deref(),
+                s.interpolation,
+                is_active,
+            )?;
 
-            if gradient_stops.is_empty() {
-                return Err(Error::new(ErrorKind::InvalidData, "No valid colors found"));
+            // Only the stops that parsed successfully count towards the gradient.
+            match gradient_stops.as_slice() {
+                [] => return Err(Error::new(ErrorKind::InvalidData, "No valid colors found")),
+                [stop] => return Ok(solid_with_alpha_opacity(stop.color)),
+                _ => {}
             }
 
             let direction = parse_gradient_direction(&s.direction)?;
 
-            Ok(Color::Gradient(Gradient {
-                gradient_stops,
+            Ok(Color::Gradient(Gradient::new(
                 direction,
-                brush: None,
-            }))
+                gradient_stops,
+                GradientShape::Linear,
+            )))
         }
     }
 }
@@ -69,38 +128,170 @@ pub fn parse_color_mapping(s: ColorMapping) -> Result<Color> {
 /// # Arguments
 ///
 /// - `colors`: A slice of strings representing color values in CSS-compatible format.
+/// - `positions`: An optional slice of explicit stop positions, one per color. When `None`, the
+///   stops are spaced evenly.
+/// - `interpolation`: The interpolation between adjacent stops. Non-linear modes insert
+///   synthetic stops between each pair of colors.
+/// - `is_active`: An optional flag indicating whether the color is active.
 ///
 /// # Returns
 ///
-/// - `Ok(Vec<D2D1_GRADIENT_STOP>)`: A vector of gradient stops for use with Direct2D.
-/// - `Err(Error)`: An error if color parsing fails.
+/// - `Ok(Vec<D2D1_GRADIENT_STOP>)`: A vector of gradient stops for use with Direct2D, sorted by
+///   position. Colors that fail to parse are skipped, and when no explicit positions are given the
+///   remaining stops are spaced evenly. Repeated colors are kept as separate stops.
+/// - `Err(Error)`: An `ErrorKind::InvalidData` error if the positions don't match the colors or
+///   lie outside 0.0 to 1.0, a color is itself a gradient, or the step count exceeds
+///   `MAX_GRADIENT_STEPS`.
 ///
 /// # Examples
 ///
-/// ```rust
-/// let stops = generate_gradient_stops(&vec!["#FF0000".to_string(), "#00FF00".to_string()])?;
+/// ```rust,ignore
+/// let stops = generate_gradient_stops(
+///     &vec!["#FF0000".to_string(), "#00FF00".to_string()],
+///     Some(&[0.0, 0.8]),
+///     GradientInterpolation::Linear,
+///     None,
+/// )?;
 /// ```
-fn generate_gradient_stops(colors: &[String]) -> Result<Vec<D2D1_GRADIENT_STOP>> {
+fn generate_gradient_stops(
+    colors: &[String],
+    positions: Option<&[f32]>,
+    interpolation: GradientInterpolation,
+    is_active: Option<bool>,
+) -> Result<Vec<D2D1_GRADIENT_STOP>> {
     let num_colors = colors.len();
-    let step = 1.0 / (num_colors - 1) as f32;
 
-    let stops: Vec<D2D1_GRADIENT_STOP> = colors
-        .iter()
+    interpolation.validate()?;
+
+    if let Some(positions) = positions {
+        if positions.len() != num_colors {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "Expected {} gradient positions but found {}",
+                    num_colors,
+                    positions.len()
+                ),
+            ));
+        }
+
+        if let Some(position) = positions.iter().find(|p| !(0.0..=1.0).contains(*p)) {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("Gradient stop position out of range: {}", position),
+            ));
+        }
+    }
+
+    let mut parsed: Vec<(usize, D2D1_COLOR_F)> = Vec::with_capacity(num_colors);
+
+    for (i, color) in colors.iter().enumerate() {
+        match parse_color_string(color, is_active) {
+            Ok(Color::Solid(solid)) => parsed.push((i, solid.effective_color())),
+            Ok(Color::Gradient(_)) => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "Gradients cannot be nested in a color mapping, but color {} is `{}`",
+                        i, color
+                    ),
+                ))
+            }
+            Err(_) => {} // Skip invalid colors
+        }
+    }
+
+    // The step is computed from the surviving colors so the stops still span the gradient.
+    let step = 1.0 / parsed.len().saturating_sub(1).max(1) as f32;
+
+    let mut stops: Vec<D2D1_GRADIENT_STOP> = parsed
+        .into_iter()
         .enumerate()
-        .filter_map(|(i, hex)| match parse_color_string(hex).ok()? {
-            Color::Solid(solid) => Some(D2D1_GRADIENT_STOP {
-                position: i as f32 * step,
-                color: solid.color,
-            }),
-            _ => None, // Skip invalid colors
+        .map(|(n, (i, color))| D2D1_GRADIENT_STOP {
+            position: positions.map_or(n as f32 * step, |positions| positions[i]),
+            color,
         })
         .collect();
 
-    Ok(stops)
+    // Explicit positions may be out of order, and synthetic stops must be inserted between
+    // neighbours. The sort is stable, so repeated colors and positions keep their order.
+    stops.sort_by(|a, b| a.position.total_cmp(&b.position));
+
+    Ok(interpolate_gradient_stops(stops, interpolation))
+}
+
+/// Inserts synthetic stops between each pair of adjacent stops to approximate an interpolation.
+///
+/// # Arguments
+///
+/// - `stops`: The gradient stops to interpolate between.
+/// - `interpolation`: The interpolation to approximate. `Linear` returns the stops unchanged.
+///
+/// # Returns
+///
+/// The gradient stops including the synthetic stops.
+fn interpolate_gradient_stops(
+    stops: Vec<D2D1_GRADIENT_STOP>,
+    interpolation: GradientInterpolation,
+) -> Vec<D2D1_GRADIENT_STOP> {
+    if interpolation == GradientInterpolation::Linear || stops.len() < 2 {
+        return stops;
+    }
+
+    let mut result = vec![stops[0]];
+
+    for pair in stops.windows(2) {
+        let (from, to) = (&pair[0], &pair[1]);
+        let span = to.position - from.position;
+        let stop_at = |t: f32, color_t: f32| D2D1_GRADIENT_STOP {
+            position: from.position + span * t,
+            color: lerp_color(&from.color, &to.color, color_t),
+        };
+
+        match interpolation {
+            GradientInterpolation::Linear => {}
+            GradientInterpolation::EaseInOut => {
+                for i in 1..EASE_IN_OUT_SEGMENTS {
+                    let t = i as f32 / EASE_IN_OUT_SEGMENTS as f32;
+                    // Smoothstep easing, which is flat at both ends.
+                    result.push(stop_at(t, t * t * (3.0 - 2.0 * t)));
+                }
+            }
+            GradientInterpolation::Steps(steps) => {
+                let color_t = |i: u32| {
+                    if steps > 1 {
+                        i as f32 / (steps - 1) as f32
+                    } else {
+                        0.0
+                    }
+                };
+
+                // Each step boundary has two stops at the same position to form a hard edge.
+                for i in 1..steps.max(1) {
+                    let t = i as f32 / steps as f32;
+                    result.push(stop_at(t, color_t(i - 1)));
+                    result.push(stop_at(t, color_t(i)));
+                }
+
+                if steps <= 1 {
+                    result.push(stop_at(1.0, 0.0));
+                }
+            }
+        }
+
+        result.push(*to);
+    }
+
+    result
 }
 
 /// Parses a gradient direction into `GradientCoordinates`.
 ///
+/// Angles with a `deg`, `grad`, `rad` or `turn` unit are converted here, wrapping negative
+/// angles and angles beyond a full turn, as are numeric `GradientDirection::Angle` degrees. The
+/// eight keyword directions (`to top`, `to right`, `to bottom right`, ...) are converted by
+/// `parse_direction_keyword`. Other directions are delegated to `GradientCoordinates::try_from`.
+///
 /// # Arguments
 ///
 /// - `direction`: A `GradientDirection` enum specifying the gradient's direction or coordinates.
@@ -108,31 +299,105 @@ fn generate_gradient_stops(colors: &[String]) -> Result<Vec<D2D1_GRADIENT_STOP>>
 /// # Returns
 ///
 /// - `Ok(GradientCoordinates)`: A valid gradient coordinate mapping.
-/// - `Err(Error)`: An error if the direction is invalid.
+/// - `Err(Error)`: An `ErrorKind::InvalidGradientCoordinates` error naming the invalid direction.
 ///
 /// # Examples
 ///
-/// ```rust
+/// ```rust,ignore
 /// let direction = GradientDirection::Direction("90deg".to_string());
 /// let coordinates = parse_gradient_direction(&direction)?;
 /// ```
-fn parse_gradient_direction(direction: &GradientDirection) -> Result<GradientCoordinates> {
+pub(crate) fn parse_gradient_direction(
+    direction: &GradientDirection,
+) -> Result<GradientCoordinates> {
     match direction {
         GradientDirection::Direction(dir) => {
+            if let Some(angle) = parse_angle(dir) {
+                return Ok(angle_to_coordinates(angle));
+            }
+
+            if let Some(coordinates) = parse_direction_keyword(dir) {
+                return coordinates;
+            }
+
             GradientCoordinates::try_from(dir.as_str()).map_err(|e| {
                 Error::new(
-                    ErrorKind::InvalidData,
-                    format!("Invalid gradient direction: {}", e),
+                    ErrorKind::InvalidGradientCoordinates,
+                    format!("Invalid gradient direction `{}`: {}", dir, e),
                 )
             })
         }
+        GradientDirection::Angle(angle) => Ok(angle_to_coordinates(*angle)),
         GradientDirection::Coordinates(coords) => Ok(coords.clone()),
     }
 }
 
-/// Parses a CSS color string into a `Color`.
+/// Registers a custom named color that `parse_color_string` resolves in addition to the CSS
+/// named colors.
+///
+/// Names are case-insensitive and surrounding whitespace is ignored. Registering an existing name
+/// replaces its color, and registered names take precedence over CSS named colors.
+///
+/// # Arguments
+///
+/// - `name`: The name of the color, e.g. `catppuccin-mauve`.
+/// - `color`: The color the name resolves to.
+///
+/// # Examples
+///
+/// ```rust
+/// # use win_color::{register_named_color, GlobalColor, GlobalColorImpl};
+/// # use windows::Win32::Graphics::Direct2D::Common::D2D1_COLOR_F;
+/// # fn main() -> win_color::Result<()> {
+/// register_named_color(
+///     "catppuccin-mauve",
+///     D2D1_COLOR_F { r: 0.796, g: 0.651, b: 0.969, a: 1.0 },
+/// );
+/// let color = GlobalColor::String("Catppuccin-Mauve".to_string()).to_color(None)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn register_named_color(name: &str, color: D2D1_COLOR_F) {
+    let mut registry = named_colors()
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    registry.insert(name.trim().to_ascii_lowercase(), color);
+}
+
+/// Looks up a color added with `register_named_color`.
+fn lookup_named_color(name: &str) -> Option<D2D1_COLOR_F> {
+    let registry = named_colors()
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    registry.get(&name.trim().to_ascii_lowercase()).copied()
+}
+
+/// Returns the registry of custom named colors, keyed by lowercase name.
+fn named_colors() -> &'static RwLock<HashMap<String, D2D1_COLOR_F>> {
+    static NAMED_COLORS: OnceLock<RwLock<HashMap<String, D2D1_COLOR_F>>> = OnceLock::new();
+    NAMED_COLORS.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// The kind of color a color string describes, as returned by `classify_color`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorKind {
+    /// A single solid color.
+    Solid,
+    /// A linear gradient.
+    LinearGradient,
+    /// A radial gradient.
+    RadialGradient,
+    /// A conic gradient.
+    ConicGradient,
+}
+
+/// Validates a color string and classifies the kind of color it describes.
 ///
-/// This function supports solid colors and gradients in CSS-compatible formats.
+/// The accent keywords and `dwm:` references are classified without resolving them, so the
+/// accent color and the registry are not read for them. Any other string, including the arguments
+/// of color and gradient functions, is parsed as by `parse_color_string` without building a
+/// Direct2D resource, and fails with the same error kinds. This makes it suitable for linting
+/// configs.
 ///
 /// # Arguments
 ///
@@ -140,112 +405,2561 @@ fn parse_gradient_direction(direction: &GradientDirection) -> Result<GradientCoo
 ///
 /// # Returns
 ///
-/// - `Ok(Color)`: A parsed `Color` object.
+/// - `Ok(ColorKind)`: The kind of color the string describes.
 /// - `Err(Error)`: An error if the input is invalid or unsupported.
 ///
 /// # Examples
 ///
 /// ```rust
-/// let color = parse_color_string("#FF0000")?;
+/// # use win_color::{classify_color, ColorKind};
+/// # fn main() -> win_color::Result<()> {
+/// assert_eq!(classify_color("#FF0000")?, ColorKind::Solid);
+/// assert_eq!(classify_color("radial-gradient(#FF0000, #0000FF)")?, ColorKind::RadialGradient);
+/// # Ok(())
+/// # }
 /// ```
-pub fn parse_color_string(s: &str) -> Result<Color> {
-    let css_color = CssColor::from_html(s).map_err(|e| {
-        Error::new(
-            ErrorKind::InvalidInput,
-            format!("CSS parsing failed: {}", e),
-        )
-    })?;
+pub fn classify_color(s: &str) -> Result<ColorKind> {
+    let keyword = s.trim().to_ascii_lowercase();
+    if matches!(
+        keyword.as_str(),
+        "transparent" | "none" | "accent" | "accent_dark" | "accent_light"
+    ) || strip_dwm_reference(s).is_some()
+    {
+        return Ok(ColorKind::Solid);
+    }
 
-    parse_solid_color(&css_color)
-        .or_else(|_| parse_gradient(&css_color))
-        .map_err(|_| {
-            Error::new(
-                ErrorKind::InvalidInput,
-                "Input does not represent a valid solid color or gradient",
-            )
-        })
+    match parse_color_string(s, None)? {
+        Color::Solid(_) => Ok(ColorKind::Solid),
+        Color::Gradient(gradient) => Ok(match gradient.shape {
+            GradientShape::Linear => ColorKind::LinearGradient,
+            GradientShape::Radial => ColorKind::RadialGradient,
+            GradientShape::Conic => ColorKind::ConicGradient,
+        }),
+    }
 }
 
-/// Parses a `CssColor` into a solid `Color`.
+/// Parses a CSS color string into a `Color`, falling back to a given color on failure.
+///
+/// This keeps rendering working when a color cannot be resolved, for example when the system
+/// accent color cannot be retrieved.
 ///
 /// # Arguments
 ///
-/// - `css_color`: A `CssColor` object representing a solid color.
+/// - `s`: A string containing the CSS color definition.
+/// - `fallback`: The color returned if parsing fails for any reason.
+/// - `is_active`: An optional flag indicating whether the color is active, as for
+///   `parse_color_string`.
 ///
 /// # Returns
 ///
-/// - `Ok(Color::Solid)`: A `Solid` color object.
-/// - `Err(Error)`: An error if the input is not a solid color.
+/// The parsed `Color`, or `fallback` if the input is invalid or unsupported.
 ///
 /// # Examples
 ///
 /// ```rust
-/// let color = parse_solid_color(&CssColor::from_html("#FF0000")?)?;
+/// # use win_color::{parse_color_string_or, Color};
+/// let color = parse_color_string_or("accent", Color::default(), None);
 /// ```
-fn parse_solid_color(css_color: &CssColor) -> Result<Color> {
-    let solid = css_color
-        .to_solid()
-        .map_err(|_| Error::new(ErrorKind::InvalidInput, "Not a solid color"))?;
-    let normalized_rgba = solid.to_normalized_rgba();
-    let color = D2D1_COLOR_F {
-        r: normalized_rgba.r,
-        g: normalized_rgba.g,
-        b: normalized_rgba.b,
-        a: normalized_rgba.a,
-    };
-    Ok(Color::Solid(Solid { color, brush: None }))
+pub fn parse_color_string_or(s: &str, fallback: Color, is_active: Option<bool>) -> Color {
+    parse_color_string(s, is_active).unwrap_or(fallback)
 }
 
-/// Parses a `CssColor` into a gradient `Color`.
+/// Parses a CSS color string into a `Color`, resolving the `currentColor` keyword to a context
+/// color.
+///
+/// This lets a component inherit a color, such as a theme color, from its caller. Any other
+/// string is parsed with `parse_color_string` and the context is ignored.
 ///
 /// # Arguments
 ///
-/// - `css_color`: A `CssColor` object representing a gradient.
+/// - `s`: A string containing the CSS color definition or `currentColor`, matched
+///   case-insensitively.
+/// - `context`: The color `currentColor` resolves to.
+/// - `is_active`: An optional flag indicating whether the color is active, as for
+///   `parse_color_string`.
 ///
 /// # Returns
 ///
-/// - `Ok(Color::Gradient)`: A `Gradient` color object.
-/// - `Err(Error)`: An error if the input is not a gradient.
+/// - `Ok(Color)`: A clone of `context` for `currentColor`, or the parsed `Color` otherwise.
+/// - `Err(Error)`: An error if the input is invalid or unsupported.
 ///
 /// # Examples
 ///
 /// ```rust
-/// let color = parse_gradient(&CssColor::from_html("linear-gradient(to right, #FF0000, #00FF00)")?)?;
+/// # use win_color::{parse_color_with_context, GlobalColor, GlobalColorImpl};
+/// # fn main() -> win_color::Result<()> {
+/// let theme = GlobalColor::String("#89b4fa".to_string()).to_color(None)?;
+/// let color = parse_color_with_context("currentColor", &theme, None)?;
+/// # Ok(())
+/// # }
 /// ```
-fn parse_gradient(css_color: &CssColor) -> Result<Color> {
-    let gradient = css_color
-        .to_gradient()
-        .map_err(|_| Error::new(ErrorKind::InvalidInput, "Not a gradient"))?;
-    let num_colors = gradient.colors.len();
-    let step = 1.0 / (num_colors - 1) as f32;
+pub fn parse_color_with_context(
+    s: &str,
+    context: &Color,
+    is_active: Option<bool>,
+) -> Result<Color> {
+    if s.trim().eq_ignore_ascii_case("currentcolor") {
+        return Ok(context.clone());
+    }
 
-    let gradient_stops: Vec<D2D1_GRADIENT_STOP> = gradient
-        .colors
+    parse_color_string(s, is_active)
+}
+
+/// Parses comma-separated color layers, as in a CSS `background` with several gradients.
+///
+/// The string is split on commas that are not nested inside parentheses, and each layer is parsed
+/// with `parse_color_string`. The layers are returned in the order given, so as in CSS the first
+/// layer is the topmost one.
+///
+/// # Arguments
+///
+/// - `s`: A string containing one or more comma-separated colors or gradients.
+/// - `is_active`: An optional flag indicating whether the colors are active, as for
+///   `parse_color_string`.
+///
+/// # Returns
+///
+/// - `Ok(Vec<Color>)`: The parsed layers.
+/// - `Err(Error)`: The error of the first layer that fails to parse, or an
+///   `ErrorKind::InvalidInput` error if a layer is empty.
+///
+/// # Examples
+///
+/// ```rust
+/// # use win_color::parse_color_layers;
+/// # fn main() -> win_color::Result<()> {
+/// let layers = parse_color_layers(
+///     "linear-gradient(to right, #ff000080, transparent), #1e1e2e",
+///     None,
+/// )?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn parse_color_layers(s: &str, is_active: Option<bool>) -> Result<Vec<Color>> {
+    split_top_level(s)
         .into_iter()
-        .enumerate()
-        .map(|(i, solid)| {
-            let normalized_rgba = solid.to_normalized_rgba();
-            let color = D2D1_COLOR_F {
-                r: normalized_rgba.r,
-                g: normalized_rgba.g,
-                b: normalized_rgba.b,
-                a: normalized_rgba.a,
-            };
-            D2D1_GRADIENT_STOP {
-                position: i as f32 * step,
-                color,
+        .map(|layer| {
+            if layer.is_empty() {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("Empty color layer in: {}", s),
+                ));
             }
+
+            parse_color_string(layer, is_active)
         })
-        .collect();
+        .collect()
+}
 
-    let direction = GradientCoordinates {
-        start: gradient.direction.start,
-        end: gradient.direction.end,
-    };
+/// Lists the color syntaxes accepted by `parse_color_string`, for use in autocompletion or
+/// documentation.
+///
+/// # Returns
+///
+/// A static list of labels such as `"hex"`, `"rgb()"` or `"linear-gradient()"`. Functional
+/// syntaxes end with `()`, and `"named"` covers both registered and CSS named colors.
+///
+/// # Examples
+///
+/// ```rust
+/// # use win_color::supported_formats;
+/// assert!(supported_formats().contains(&"hex"));
+/// ```
+pub fn supported_formats() -> &'static [&'static str] {
+    &[
+        "hex",
+        "transparent",
+        "none",
+        "accent",
+        "accent_dark",
+        "accent_light",
+        "dwm:",
+        "rgb()",
+        "rgba()",
+        "hsl()",
+        "hsla()",
+        "cmyk()",
+        "oklab()",
+        "oklch()",
+        "mix()",
+        "color-mix()",
+        "linear-gradient()",
+        "repeating-linear-gradient()",
+        "radial-gradient()",
+        "repeating-radial-gradient()",
+        "conic-gradient()",
+        "repeating-conic-gradient()",
+        "named",
+    ]
+}
 
-    Ok(Color::Gradient(Gradient {
-        direction,
-        gradient_stops,
-        brush: None,
-    }))
+/// Parses a CSS color string into a `Color`.
+///
+/// This function supports solid colors and gradients in CSS-compatible formats, as well as the
+/// `accent`, `accent_dark` and `accent_light` keywords which resolve to the Windows accent color.
+/// The `transparent` keyword resolves to transparent black, with every channel exactly 0.0.
+/// The `none` keyword resolves to `ColorImpl::none`, which is transparent as well but is never
+/// drawn.
+/// A `dwm:<value>` reference, such as `dwm:ColorizationColor`, reads the named DWORD from the
+/// DWM registry key with `dwm_registry_color`.
+/// The `repeating-` variants of the gradient functions set the `Wrap` extend mode.
+/// Names added with `register_named_color` are resolved before the CSS named colors. The accepted
+/// syntaxes are listed by `supported_formats`.
+///
+/// # Arguments
+///
+/// - `s`: A string containing the CSS color definition.
+/// - `is_active`: An optional flag indicating whether the color is active. When it is
+///   `Some(false)`, the `accent` keyword resolves to the darkened accent color (the same as
+///   `accent_dark`). `None` is treated as active.
+///
+/// # Returns
+///
+/// - `Ok(Color)`: A parsed `Color` object. The alpha of a solid color is stored in
+///   `Solid::opacity`, with `color.a` set to 1.0.
+/// - `Err(Error)`: An error if the input is invalid or unsupported.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// let color = parse_color_string("#FF0000", None)?;
+/// ```
+pub fn parse_color_string(s: &str, is_active: Option<bool>) -> Result<Color> {
+    if s.trim().starts_with('#') {
+        let color = parse_hex_color(s.trim())?;
+        return Ok(solid_with_alpha_opacity(color));
+    }
+
+    match s.trim().to_ascii_lowercase().as_str() {
+        "transparent" => return Ok(Color::transparent()),
+        "none" => return Ok(Color::none()),
+        "accent" if is_active != Some(false) => return accent_color(),
+        "accent" | "accent_dark" => {
+            let color = darken(accent_d2d1_color()?, ACCENT_DARKEN_PERCENTAGE);
+            return Ok(solid_with_alpha_opacity(color));
+        }
+        "accent_light" => {
+            let color = lighten(accent_d2d1_color()?, ACCENT_LIGHTEN_PERCENTAGE);
+            return Ok(solid_with_alpha_opacity(color));
+        }
+        _ => {}
+    }
+
+    if let Some(name) = strip_dwm_reference(s) {
+        let color = dwm_registry_color(name)?;
+        return Ok(solid_with_alpha_opacity(color));
+    }
+
+    for name in ["rgb", "rgba", "hsl", "hsla"] {
+        if let Some(args) = strip_function(s, name) {
+            let color = parse_functional_color(name, args)?;
+            return Ok(solid_with_alpha_opacity(color));
+        }
+    }
+
+    if let Some(args) = strip_function(s, "cmyk") {
+        let color = parse_cmyk_color(args)?;
+        return Ok(solid_with_alpha_opacity(color));
+    }
+
+    for name in ["oklab", "oklch"] {
+        if let Some(args) = strip_function(s, name) {
+            let color = parse_oklab_color(name, args)?;
+            return Ok(solid_with_alpha_opacity(color));
+        }
+    }
+
+    if let Some(args) = strip_function(s, "mix") {
+        let color = parse_mix_color(args, is_active)?;
+        return Ok(solid_with_alpha_opacity(color));
+    }
+
+    if let Some(args) = strip_function(s, "color-mix") {
+        let color = parse_color_mix(args, is_active)?;
+        return Ok(solid_with_alpha_opacity(color));
+    }
+
+    let gradient_parsers: [(&str, GradientParser); 3] = [
+        ("linear-gradient", parse_linear_gradient),
+        ("radial-gradient", parse_radial_gradient),
+        ("conic-gradient", parse_conic_gradient),
+    ];
+
+    for (name, parse_gradient) in gradient_parsers {
+        if let Some(args) = strip_function(s, &format!("repeating-{}", name)) {
+            let mut color = parse_gradient(args, is_active)?;
+            if let Color::Gradient(ref mut gradient) = color {
+                gradient.extend_mode = GradientExtendMode::Wrap;
+            }
+            return Ok(color);
+        }
+
+        if let Some(args) = strip_function(s, name) {
+            return parse_gradient(args, is_active);
+        }
+    }
+
+    if let Some(color) = lookup_named_color(s) {
+        return Ok(solid_with_alpha_opacity(color));
+    }
+
+    let css_color = CssColor::from_html(s).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("CSS parsing failed: {}", e),
+        )
+    })?;
+
+    if let Ok(color) = parse_solid_color(&css_color) {
+        return Ok(color);
+    }
+
+    parse_gradient(&css_color)
+}
+
+/// Retrieves the Windows accent color as a solid `Color`.
+///
+/// The accent color is read from the Desktop Window Manager colorization color, which follows the
+/// user's Windows personalization settings.
+///
+/// # Returns
+///
+/// - `Ok(Color::Solid)`: The current accent color.
+/// - `Err(Error)`: An `ErrorKind::InvalidAccent` error if the accent color cannot be read.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use win_color::accent_color;
+/// # fn main() -> win_color::Result<()> {
+/// let accent = accent_color()?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn accent_color() -> Result<Color> {
+    let color = accent_d2d1_color()?;
+    Ok(solid_with_alpha_opacity(color))
+}
+
+/// Reads the DWM colorization color and converts it into a `D2D1_COLOR_F`.
+///
+/// # Returns
+///
+/// - `Ok(D2D1_COLOR_F)`: The accent color with channels in the range 0.0 to 1.0.
+/// - `Err(Error)`: An `ErrorKind::InvalidAccent` error if the DWM query fails.
+fn accent_d2d1_color() -> Result<D2D1_COLOR_F> {
+    let mut colorization: u32 = 0;
+    let mut opaque_blend = BOOL::default();
+
+    unsafe { DwmGetColorizationColor(&mut colorization, &mut opaque_blend) }.map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidAccent,
+            format!("Failed to retrieve the accent color: {}", e),
+        )
+    })?;
+
+    // The colorization color is packed as 0xAARRGGBB; the accent is always drawn opaque.
+    Ok(D2D1_COLOR_F {
+        r: ((colorization >> 16) & 0xFF) as f32 / 255.0,
+        g: ((colorization >> 8) & 0xFF) as f32 / 255.0,
+        b: (colorization & 0xFF) as f32 / 255.0,
+        a: 1.0,
+    })
+}
+
+/// Strips the case-insensitive `dwm:` prefix from a DWM registry color reference.
+///
+/// # Returns
+///
+/// The trimmed registry value name, or `None` if `s` is not a DWM reference.
+fn strip_dwm_reference(s: &str) -> Option<&str> {
+    let s = s.trim();
+    let (prefix, name) = (s.get(..4)?, s.get(4..)?);
+    prefix.eq_ignore_ascii_case("dwm:").then_some(name.trim())
+}
+
+/// Reads a color DWORD from the `HKCU\Software\Microsoft\Windows\DWM` registry key and
+/// converts it into a `D2D1_COLOR_F`.
+///
+/// Most DWM colors, such as `ColorizationColor` and `ColorizationAfterglow`, are packed as
+/// `0xAARRGGBB`, while `AccentColor` is packed as `0xAABBGGRR`. As with the accent color, the
+/// result is always opaque.
+///
+/// # Arguments
+///
+/// - `name`: The name of the registry value, such as `ColorizationColor`.
+///
+/// # Returns
+///
+/// - `Ok(D2D1_COLOR_F)`: The color with channels in the range 0.0 to 1.0.
+/// - `Err(Error)`: An `ErrorKind::InvalidAccent` error if the value is absent or not a DWORD.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// let color = dwm_registry_color("ColorizationColor")?;
+/// ```
+fn dwm_registry_color(name: &str) -> Result<D2D1_COLOR_F> {
+    let value_name: Vec<u16> = name.encode_utf16().chain(Some(0)).collect();
+    let mut value: u32 = 0;
+    let mut size = std::mem::size_of::<u32>() as u32;
+
+    unsafe {
+        RegGetValueW(
+            HKEY_CURRENT_USER,
+            w!("Software\\Microsoft\\Windows\\DWM"),
+            PCWSTR(value_name.as_ptr()),
+            RRF_RT_REG_DWORD,
+            None,
+            Some(&mut value as *mut u32 as *mut _),
+            Some(&mut size),
+        )
+    }
+    .ok()
+    .map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidAccent,
+            format!("Failed to read the DWM color `{}`: {}", name, e),
+        )
+    })?;
+
+    Ok(unpack_dwm_color(name, value))
+}
+
+/// Unpacks a DWM registry color DWORD into an opaque `D2D1_COLOR_F`.
+///
+/// `AccentColor` is packed as `0xAABBGGRR`, every other value as `0xAARRGGBB`.
+///
+/// # Arguments
+///
+/// - `name`: The name of the registry value the DWORD was read from.
+/// - `value`: The packed color.
+///
+/// # Returns
+///
+/// The color with channels in the range 0.0 to 1.0 and an alpha of 1.0.
+fn unpack_dwm_color(name: &str, value: u32) -> D2D1_COLOR_F {
+    let (r, b) = if name.eq_ignore_ascii_case("AccentColor") {
+        (value & 0xFF, (value >> 16) & 0xFF)
+    } else {
+        ((value >> 16) & 0xFF, value & 0xFF)
+    };
+
+    D2D1_COLOR_F {
+        r: r as f32 / 255.0,
+        g: ((value >> 8) & 0xFF) as f32 / 255.0,
+        b: b as f32 / 255.0,
+        a: 1.0,
+    }
+}
+
+/// Parses a hex color string into a `D2D1_COLOR_F`.
+///
+/// Supports the `#RGB`, `#RGBA`, `#RRGGBB` and `#RRGGBBAA` forms. When an alpha component is
+/// present it is normalized into `D2D1_COLOR_F.a`, otherwise the color is fully opaque.
+///
+/// # Arguments
+///
+/// - `s`: A string containing the hex color, including the leading `#`.
+///
+/// # Returns
+///
+/// - `Ok(D2D1_COLOR_F)`: The parsed color with channels in the range 0.0 to 1.0.
+/// - `Err(Error)`: An `ErrorKind::InvalidHex` error if the string is malformed.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// let color = parse_hex_color("#89b4faCC")?;
+/// ```
+pub(crate) fn parse_hex_color(s: &str) -> Result<D2D1_COLOR_F> {
+    let invalid_hex = || Error::new(ErrorKind::InvalidHex, format!("Invalid hex color: {}", s));
+
+    let hex = s.strip_prefix('#').ok_or_else(invalid_hex)?;
+
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(invalid_hex());
+    }
+
+    let channels: Vec<u8> = match hex.len() {
+        // Shorthand forms double each digit, e.g. `#F0A` is `#FF00AA`.
+        3 | 4 => hex
+            .chars()
+            .map(|c| c.to_digit(16).map(|d| (d * 17) as u8))
+            .collect::<Option<Vec<u8>>>()
+            .ok_or_else(invalid_hex)?,
+        6 | 8 => (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+            .collect::<Option<Vec<u8>>>()
+            .ok_or_else(invalid_hex)?,
+        _ => return Err(invalid_hex()),
+    };
+
+    Ok(D2D1_COLOR_F {
+        r: channels[0] as f32 / 255.0,
+        g: channels[1] as f32 / 255.0,
+        b: channels[2] as f32 / 255.0,
+        a: channels.get(3).map_or(1.0, |&a| a as f32 / 255.0),
+    })
+}
+
+/// Parses the arguments of an `rgb()`, `rgba()`, `hsl()` or `hsla()` color into a `D2D1_COLOR_F`.
+///
+/// RGB channels accept numbers from 0 to 255 or percentages. The hue accepts a number of degrees
+/// or an angle with a unit, while saturation and lightness accept percentages from 0% to 100%.
+/// The optional alpha accepts a number from 0.0 to 1.0 or a percentage.
+///
+/// Both the legacy comma-separated syntax (`rgb(255, 0, 0, 0.5)`) and the modern
+/// space-separated syntax with a slash before the alpha (`rgb(255 0 0 / 50%)`) are accepted.
+///
+/// # Arguments
+///
+/// - `name`: The function name, one of `rgb`, `rgba`, `hsl` or `hsla`.
+/// - `args`: The arguments found between the parentheses.
+///
+/// # Returns
+///
+/// - `Ok(D2D1_COLOR_F)`: The parsed color with channels in the range 0.0 to 1.0.
+/// - `Err(Error)`: An `ErrorKind::InvalidRgb` error naming the offending component.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// let color = parse_functional_color("rgba", "255, 0, 0, 0.5")?;
+/// let color = parse_functional_color("rgb", "255 0 0 / 50%")?;
+/// ```
+fn parse_functional_color(name: &str, args: &str) -> Result<D2D1_COLOR_F> {
+    let invalid = |component: &str| {
+        Error::new(
+            ErrorKind::InvalidRgb,
+            format!("Invalid component `{}` in {}({})", component, name, args),
+        )
+    };
+
+    let parts = if args.contains(',') {
+        if args.contains('/') {
+            return Err(Error::new(
+                ErrorKind::InvalidRgb,
+                format!("Cannot mix commas and a slash in {}({})", name, args),
+            ));
+        }
+
+        split_top_level(args)
+    } else {
+        split_space_separated(name, args)?
+    };
+
+    if !(3..=4).contains(&parts.len()) {
+        return Err(Error::new(
+            ErrorKind::InvalidRgb,
+            format!("Expected 3 or 4 components in {}({})", name, args),
+        ));
+    }
+
+    let a = match parts.get(3) {
+        Some(alpha) => parse_fraction(alpha, 1.0).ok_or_else(|| invalid(alpha))?,
+        None => 1.0,
+    };
+
+    if name.starts_with("rgb") {
+        let channel = |part: &str| parse_fraction(part, 255.0).ok_or_else(|| invalid(part));
+
+        Ok(D2D1_COLOR_F {
+            r: channel(parts[0])?,
+            g: channel(parts[1])?,
+            b: channel(parts[2])?,
+            a,
+        })
+    } else {
+        let h = parse_angle(parts[0])
+            .or_else(|| parts[0].parse::<f32>().ok().filter(|h| h.is_finite()))
+            .ok_or_else(|| invalid(parts[0]))?;
+        let percentage = |part: &str| {
+            part.strip_suffix('%')
+                .unwrap_or(part)
+                .trim()
+                .parse::<f32>()
+                .ok()
+                .filter(|value| (0.0..=100.0).contains(value))
+                .ok_or_else(|| invalid(part))
+        };
+
+        Ok(hsla_to_d2d1(Hsla {
+            h: h.rem_euclid(360.0),
+            s: percentage(parts[1])?,
+            l: percentage(parts[2])?,
+            a,
+        }))
+    }
+}
+
+/// Splits the space-separated arguments of a functional color, such as `255 0 0 / 50%`, into
+/// its components.
+///
+/// The alpha, if any, follows a single slash and is returned as the fourth component.
+///
+/// # Returns
+///
+/// - `Ok(Vec<&str>)`: The components, with the alpha last.
+/// - `Err(Error)`: An `ErrorKind::InvalidRgb` error if the slash is not followed by exactly one
+///   alpha component, or there is more than one slash.
+fn split_space_separated<'a>(name: &str, args: &'a str) -> Result<Vec<&'a str>> {
+    let malformed = || {
+        Error::new(
+            ErrorKind::InvalidRgb,
+            format!("Malformed alpha after `/` in {}({})", name, args),
+        )
+    };
+
+    let (channels, alpha) = match args.split_once('/') {
+        Some((channels, alpha)) => (channels, Some(alpha)),
+        None => (args, None),
+    };
+
+    let mut parts: Vec<&str> = channels.split_whitespace().collect();
+
+    if let Some(alpha) = alpha {
+        match alpha.split_whitespace().collect::<Vec<_>>().as_slice() {
+            [alpha] if !alpha.contains('/') && parts.len() == 3 => parts.push(alpha),
+            _ => return Err(malformed()),
+        }
+    }
+
+    Ok(parts)
+}
+
+/// Parses the arguments of a `cmyk()` color into a `D2D1_COLOR_F`.
+///
+/// Each of the cyan, magenta, yellow and black components accepts a percentage from 0% to 100%
+/// or a number from 0.0 to 1.0. The resulting color is fully opaque.
+///
+/// # Arguments
+///
+/// - `args`: The comma-separated arguments found between the parentheses.
+///
+/// # Returns
+///
+/// - `Ok(D2D1_COLOR_F)`: The converted color with channels in the range 0.0 to 1.0.
+/// - `Err(Error)`: An `ErrorKind::InvalidInput` error naming the offending component.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// let color = parse_cmyk_color("0%, 100%, 100%, 0%")?; // red
+/// ```
+fn parse_cmyk_color(args: &str) -> Result<D2D1_COLOR_F> {
+    let parts = split_top_level(args);
+
+    if parts.len() != 4 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("Expected 4 components in cmyk({})", args),
+        ));
+    }
+
+    let component = |part: &str| {
+        parse_fraction(part, 1.0).ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("Invalid component `{}` in cmyk({})", part, args),
+            )
+        })
+    };
+
+    let (c, m, y, k) = (
+        component(parts[0])?,
+        component(parts[1])?,
+        component(parts[2])?,
+        component(parts[3])?,
+    );
+
+    Ok(D2D1_COLOR_F {
+        r: (1.0 - c) * (1.0 - k),
+        g: (1.0 - m) * (1.0 - k),
+        b: (1.0 - y) * (1.0 - k),
+        a: 1.0,
+    })
+}
+
+/// Parses the arguments of an `oklab()` or `oklch()` color into a `D2D1_COLOR_F`.
+///
+/// The components are space-separated, optionally followed by `/` and an alpha. The lightness
+/// accepts a percentage or a number from 0.0 to 1.0. For `oklab()`, the `a` and `b` axes accept
+/// numbers or percentages, where 100% is 0.4. For `oklch()`, the chroma accepts a non-negative
+/// number or percentage, where 100% is 0.4, and the hue accepts an angle or a number of degrees.
+///
+/// Colors outside the sRGB gamut are clamped per channel, so very saturated colors may shift in
+/// hue and lightness compared to how they are rendered on a wide gamut display.
+///
+/// # Arguments
+///
+/// - `name`: The function name, either `oklab` or `oklch`.
+/// - `args`: The arguments found between the parentheses.
+///
+/// # Returns
+///
+/// - `Ok(D2D1_COLOR_F)`: The converted color with channels in the range 0.0 to 1.0.
+/// - `Err(Error)`: An `ErrorKind::InvalidInput` error naming the offending component, or if the
+///   component count is wrong.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// let color = parse_oklab_color("oklch", "62.8% 0.2577 29.23")?; // red
+/// let color = parse_oklab_color("oklab", "0.628 0.225 0.126 / 50%")?;
+/// ```
+fn parse_oklab_color(name: &str, args: &str) -> Result<D2D1_COLOR_F> {
+    let invalid = |component: &str| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("Invalid component `{}` in {}({})", component, name, args),
+        )
+    };
+
+    let (channels, alpha) = match args.split_once('/') {
+        Some((channels, alpha)) => (channels, Some(alpha.trim())),
+        None => (args, None),
+    };
+
+    let parts: Vec<&str> = channels.split_whitespace().collect();
+
+    if parts.len() != 3 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "Expected 3 space-separated components in {}({})",
+                name, args
+            ),
+        ));
+    }
+
+    let alpha = match alpha {
+        Some(alpha) => parse_fraction(alpha, 1.0).ok_or_else(|| invalid(alpha))?,
+        None => 1.0,
+    };
+
+    let lightness = parse_fraction(parts[0], 1.0).ok_or_else(|| invalid(parts[0]))?;
+
+    // Percentages of the `a`, `b` and chroma components are relative to 0.4.
+    let number = |part: &str| {
+        let value = match part.strip_suffix('%') {
+            Some(percentage) => percentage.trim().parse::<f32>().map(|value| value * 0.004),
+            None => part.parse::<f32>(),
+        };
+
+        value
+            .ok()
+            .filter(|value| value.is_finite())
+            .ok_or_else(|| invalid(part))
+    };
+
+    let (a, b) = if name == "oklch" {
+        let chroma = number(parts[1]).and_then(|chroma| {
+            (chroma >= 0.0)
+                .then_some(chroma)
+                .ok_or_else(|| invalid(parts[1]))
+        })?;
+        let hue = parse_angle(parts[2])
+            .or_else(|| parts[2].parse::<f32>().ok().filter(|h| h.is_finite()))
+            .ok_or_else(|| invalid(parts[2]))?
+            .to_radians();
+
+        (chroma * hue.cos(), chroma * hue.sin())
+    } else {
+        (number(parts[1])?, number(parts[2])?)
+    };
+
+    Ok(oklab_to_d2d1(lightness, a, b, alpha))
+}
+
+/// Parses the arguments of a `mix()` color into a `D2D1_COLOR_F`.
+///
+/// The two colors are parsed with `parse_color_string` and interpolated linearly, where the
+/// percentage is the amount of the second color, from 0% (the first color) to 100% (the second
+/// color). A plain number from 0.0 to 1.0 is accepted as well.
+///
+/// # Arguments
+///
+/// - `args`: The comma-separated arguments found between the parentheses.
+/// - `is_active`: An optional flag indicating whether the color is active.
+///
+/// # Returns
+///
+/// - `Ok(D2D1_COLOR_F)`: The mixed color.
+/// - `Err(Error)`: An error if either color fails to parse, or an `ErrorKind::InvalidInput` error
+///   if a color is a gradient, the percentage is invalid or the argument count is wrong.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// let color = parse_mix_color("#ff0000, #0000ff, 50%", None)?; // purple
+/// ```
+fn parse_mix_color(args: &str, is_active: Option<bool>) -> Result<D2D1_COLOR_F> {
+    let [from, to, amount] = split_top_level(args)[..] else {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("Expected 2 colors and a percentage in mix({})", args),
+        ));
+    };
+
+    let solid = |part: &str| match parse_color_string(part, is_active)? {
+        Color::Solid(solid) => Ok(solid.effective_color()),
+        Color::Gradient(_) => Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("Cannot mix the gradient `{}` in mix({})", part, args),
+        )),
+    };
+
+    let t = parse_fraction(amount, 1.0).ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("Invalid percentage `{}` in mix({})", amount, args),
+        )
+    })?;
+
+    Ok(lerp_color(&solid(from)?, &solid(to)?, t))
+}
+
+/// Parses the arguments of a CSS `color-mix()` color into a `D2D1_COLOR_F`.
+///
+/// Only the `srgb` color space is supported. Each color may be followed by a percentage. When
+/// only one percentage is given the other color takes the remainder, when neither is given the
+/// colors are mixed equally, and when both are given they are scaled to sum to 100%.
+///
+/// # Arguments
+///
+/// - `args`: The comma-separated arguments found between the parentheses, starting with the
+///   `in <color space>` part.
+/// - `is_active`: An optional flag indicating whether the color is active.
+///
+/// # Returns
+///
+/// - `Ok(D2D1_COLOR_F)`: The mixed color.
+/// - `Err(Error)`: An error if either color fails to parse, or an `ErrorKind::InvalidInput` error
+///   if the color space is unsupported, a color is a gradient, or the percentages are invalid.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// let color = parse_color_mix("in srgb, #ff0000 40%, blue", None)?; // 40% red, 60% blue
+/// ```
+fn parse_color_mix(args: &str, is_active: Option<bool>) -> Result<D2D1_COLOR_F> {
+    let invalid = |message: String| Error::new(ErrorKind::InvalidInput, message);
+
+    let [space, from, to] = split_top_level(args)[..] else {
+        return Err(invalid(format!(
+            "Expected a color space and 2 colors in color-mix({})",
+            args
+        )));
+    };
+
+    match space.split_whitespace().collect::<Vec<_>>()[..] {
+        ["in", space] if space.eq_ignore_ascii_case("srgb") => {}
+        ["in", space] => {
+            return Err(invalid(format!(
+                "Unsupported color space `{}` in color-mix({})",
+                space, args
+            )))
+        }
+        _ => {
+            return Err(invalid(format!(
+                "Expected `in <color space>` in color-mix({})",
+                args
+            )))
+        }
+    }
+
+    let stop = |part: &str| -> Result<(D2D1_COLOR_F, Option<f32>)> {
+        let (color, percentage) = split_stop_position(part);
+        let percentage = percentage.transpose()?;
+
+        if percentage.is_some_and(|p| !(0.0..=1.0).contains(&p)) {
+            return Err(invalid(format!("Invalid percentage in `{}`", part)));
+        }
+
+        match parse_color_string(color, is_active)? {
+            Color::Solid(solid) => Ok((solid.effective_color(), percentage)),
+            Color::Gradient(_) => Err(invalid(format!(
+                "Cannot mix the gradient `{}` in color-mix({})",
+                color, args
+            ))),
+        }
+    };
+
+    let ((from, p1), (to, p2)) = (stop(from)?, stop(to)?);
+
+    let (p1, p2) = match (p1, p2) {
+        (None, None) => (0.5, 0.5),
+        (Some(p1), None) => (p1, 1.0 - p1),
+        (None, Some(p2)) => (1.0 - p2, p2),
+        (Some(p1), Some(p2)) => (p1, p2),
+    };
+
+    let total = p1 + p2;
+    if total <= 0.0 {
+        return Err(invalid(format!(
+            "The percentages in color-mix({}) must not both be 0%",
+            args
+        )));
+    }
+
+    Ok(lerp_color(&from, &to, p2 / total))
+}
+
+/// Parses a number or percentage into a fraction in the range 0.0 to 1.0.
+///
+/// Percentages are divided by 100, and plain numbers by `max`. Values outside the valid range
+/// are rejected rather than clamped.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// assert_eq!(parse_fraction("50%", 255.0), Some(0.5));
+/// assert_eq!(parse_fraction("300", 255.0), None);
+/// ```
+fn parse_fraction(s: &str, max: f32) -> Option<f32> {
+    let s = s.trim();
+    let (value, max) = match s.strip_suffix('%') {
+        Some(value) => (value, 100.0),
+        None => (s, max),
+    };
+
+    value
+        .trim()
+        .parse::<f32>()
+        .ok()
+        .filter(|value| (0.0..=max).contains(value))
+        .map(|value| value / max)
+}
+
+/// Parses a `CssColor` into a solid `Color`.
+///
+/// # Arguments
+///
+/// - `css_color`: A `CssColor` object representing a solid color.
+///
+/// # Returns
+///
+/// - `Ok(Color::Solid)`: A `Solid` color object.
+/// - `Err(Error)`: An error if the input is not a solid color.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// let color = parse_solid_color(&CssColor::from_html("#FF0000")?)?;
+/// ```
+fn parse_solid_color(css_color: &CssColor) -> Result<Color> {
+    let solid = css_color
+        .to_solid()
+        .map_err(|_| Error::new(ErrorKind::InvalidInput, "Not a solid color"))?;
+    let normalized_rgba = solid.to_normalized_rgba();
+    let color = D2D1_COLOR_F {
+        r: normalized_rgba.r,
+        g: normalized_rgba.g,
+        b: normalized_rgba.b,
+        a: normalized_rgba.a,
+    };
+    Ok(solid_with_alpha_opacity(color))
+}
+
+/// Creates a solid `Color` whose alpha is moved into `Solid::opacity`.
+///
+/// Parsed solid colors keep an opaque `color` and carry their alpha as the authored opacity, so it
+/// is applied to the brush once. `SolidImpl::effective_color` recombines the two.
+///
+/// # Arguments
+///
+/// - `color`: The parsed color, including its alpha.
+///
+/// # Returns
+///
+/// A `Color::Solid` with `color.a` set to 1.0 and `opacity` set to the alpha of `color`.
+fn solid_with_alpha_opacity(color: D2D1_COLOR_F) -> Color {
+    Color::Solid(Solid {
+        opacity: color.a,
+        ..Solid::from(D2D1_COLOR_F { a: 1.0, ..color })
+    })
+}
+
+/// Parses a `CssColor` into a gradient `Color`.
+///
+/// # Arguments
+///
+/// - `css_color`: A `CssColor` object representing a gradient.
+///
+/// # Returns
+///
+/// - `Ok(Color)`: A `Gradient` color object, or a `Solid` if the gradient has a single color.
+/// - `Err(Error)`: An `ErrorKind::InvalidInput` error if the input is not a gradient, or an
+///   `ErrorKind::InvalidData` error if it has no colors.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// let color = parse_gradient(&CssColor::from_html("linear-gradient(to right, #FF0000, #00FF00)")?)?;
+/// ```
+fn parse_gradient(css_color: &CssColor) -> Result<Color> {
+    let gradient = css_color.to_gradient().map_err(|_| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            "Input does not represent a valid solid color or gradient",
+        )
+    })?;
+    let colors: Vec<D2D1_COLOR_F> = gradient
+        .colors
+        .iter()
+        .map(|solid| {
+            let normalized_rgba = solid.to_normalized_rgba();
+            D2D1_COLOR_F {
+                r: normalized_rgba.r,
+                g: normalized_rgba.g,
+                b: normalized_rgba.b,
+                a: normalized_rgba.a,
+            }
+        })
+        .collect();
+
+    let direction = GradientCoordinates {
+        start: gradient.direction.start,
+        end: gradient.direction.end,
+    };
+
+    evenly_spaced_gradient(colors, direction)
+}
+
+/// Builds a linear gradient `Color` with the colors spaced evenly from 0.0 to 1.0.
+///
+/// # Arguments
+///
+/// - `colors`: The colors of the gradient stops, in order.
+/// - `direction`: The direction of the gradient.
+///
+/// # Returns
+///
+/// - `Ok(Color)`: A `Gradient` color object, or a `Solid` if there is a single color.
+/// - `Err(Error)`: An `ErrorKind::InvalidData` error if there are no colors.
+fn evenly_spaced_gradient(
+    colors: Vec<D2D1_COLOR_F>,
+    direction: GradientCoordinates,
+) -> Result<Color> {
+    // A single color would divide by zero below, so it is treated as a solid color instead.
+    match colors.as_slice() {
+        [] => {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "Gradient does not contain any colors",
+            ))
+        }
+        [color] => return Ok(solid_with_alpha_opacity(*color)),
+        _ => {}
+    }
+
+    let step = 1.0 / (colors.len() - 1) as f32;
+
+    let gradient_stops: Vec<D2D1_GRADIENT_STOP> = colors
+        .into_iter()
+        .enumerate()
+        .map(|(i, color)| D2D1_GRADIENT_STOP {
+            position: i as f32 * step,
+            color,
+        })
+        .collect();
+
+    Ok(Color::Gradient(Gradient::new(
+        direction,
+        gradient_stops,
+        GradientShape::Linear,
+    )))
+}
+
+/// Parses the arguments of a `linear-gradient(...)` string into a linear gradient `Color`.
+///
+/// A leading angle or keyword direction (e.g. `90deg` or `to bottom right`) sets the direction,
+/// which defaults to `to bottom`. The remaining arguments are parsed as color stops with
+/// `parse_color_stops`, so they may carry inline percentage positions.
+///
+/// # Arguments
+///
+/// - `args`: The comma-separated arguments found between the parentheses.
+/// - `is_active`: An optional flag indicating whether the color is active.
+///
+/// # Returns
+///
+/// - `Ok(Color::Gradient)`: A linear `Gradient` color object.
+/// - `Err(Error)`: An error if the direction or any color stop is invalid, or fewer than two
+///   stops are given.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// let color = parse_linear_gradient("90deg, #FF0000 20%, #0000FF 80%", None)?;
+/// ```
+fn parse_linear_gradient(args: &str, is_active: Option<bool>) -> Result<Color> {
+    let mut parts = split_top_level(args);
+    let mut direction = GradientCoordinates {
+        start: [0.5, 0.0],
+        end: [0.5, 1.0],
+    };
+
+    if let Some(first) = parts.first() {
+        let first_lower = first.trim().to_ascii_lowercase();
+
+        if parse_angle(first).is_some() || first_lower.starts_with("to ") {
+            direction = parse_gradient_direction(&GradientDirection::from(*first))?;
+            parts.remove(0);
+        }
+    }
+
+    let gradient_stops = parse_color_stops(&parts, is_active)?;
+
+    Ok(Color::Gradient(Gradient::new(
+        direction,
+        gradient_stops,
+        GradientShape::Linear,
+    )))
+}
+
+/// Parses the arguments of a `radial-gradient(...)` string into a radial gradient `Color`.
+///
+/// A leading shape, size or position argument (e.g. `circle` or `ellipse closest-side at center`)
+/// is accepted and ignored; the gradient is always centered in the window with radii spanning
+/// half of its width and height. The remaining arguments are parsed as color stops with
+/// `parse_color_stops`.
+///
+/// # Arguments
+///
+/// - `args`: The comma-separated arguments found between the parentheses.
+/// - `is_active`: An optional flag indicating whether the color is active.
+///
+/// # Returns
+///
+/// - `Ok(Color::Gradient)`: A radial `Gradient` color object.
+/// - `Err(Error)`: An error if any color stop is invalid or fewer than two stops are given.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// let color = parse_radial_gradient("circle, #FF0000, #00FF00", None)?;
+/// ```
+fn parse_radial_gradient(args: &str, is_active: Option<bool>) -> Result<Color> {
+    let mut parts = split_top_level(args);
+
+    if parts.first().is_some_and(|part| is_radial_shape(part)) {
+        parts.remove(0);
+    }
+
+    let gradient_stops = parse_color_stops(&parts, is_active)?;
+
+    let direction = GradientCoordinates {
+        start: [0.5, 0.5],
+        end: [1.0, 1.0],
+    };
+
+    Ok(Color::Gradient(Gradient::new(
+        direction,
+        gradient_stops,
+        GradientShape::Radial,
+    )))
+}
+
+/// Returns whether a `radial-gradient(...)` argument is a shape, size or position rather than a
+/// color stop.
+///
+/// The argument must consist only of the `circle` and `ellipse` shapes, the `closest-side`,
+/// `closest-corner`, `farthest-side` and `farthest-corner` extents or lengths, optionally followed
+/// by `at` and a position made of side keywords and lengths.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// assert!(is_radial_shape("ellipse farthest-corner at top left"));
+/// assert!(!is_radial_shape("#gg0000"));
+/// ```
+fn is_radial_shape(part: &str) -> bool {
+    let part = part.trim().to_ascii_lowercase();
+    let is_length = |token: &str| {
+        let (value, _, unit) = strip_string(token, &[], &["px", "%"]);
+        (unit.is_some() || value == "0") && value.parse::<f32>().is_ok()
+    };
+
+    let mut tokens = part.split_whitespace().peekable();
+    if tokens.peek().is_none() {
+        return false;
+    }
+
+    while let Some(token) = tokens.next() {
+        match token {
+            "at" => {
+                return tokens.peek().is_some()
+                    && tokens.all(|token| {
+                        matches!(token, "left" | "right" | "top" | "bottom" | "center")
+                            || is_length(token)
+                    });
+            }
+            "circle" | "ellipse" | "closest-side" | "closest-corner" | "farthest-side"
+            | "farthest-corner" => {}
+            token if is_length(token) => {}
+            _ => return false,
+        }
+    }
+
+    true
+}
+
+/// Parses the arguments of a `conic-gradient(...)` string into a conic gradient `Color`.
+///
+/// A leading `from <angle>` argument sets the starting angle, which defaults to `0deg`. Any
+/// `at <position>` part is accepted and ignored; the gradient is always centered in the window.
+/// The remaining arguments are parsed as color stops with `parse_color_stops`. The stops are
+/// kept as authored, since the linear approximation described on `GradientShape::Conic` cannot
+/// make use of extra angular stops.
+///
+/// # Arguments
+///
+/// - `args`: The comma-separated arguments found between the parentheses.
+/// - `is_active`: An optional flag indicating whether the color is active.
+///
+/// # Returns
+///
+/// - `Ok(Color::Gradient)`: A conic `Gradient` color object.
+/// - `Err(Error)`: An error if the angle or any color stop is invalid, or fewer than two stops
+///   are given.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// let color = parse_conic_gradient("from 90deg, #FF0000, #00FF00", None)?;
+/// ```
+fn parse_conic_gradient(args: &str, is_active: Option<bool>) -> Result<Color> {
+    let mut parts = split_top_level(args);
+    let mut angle = 0.0;
+
+    if let Some(first) = parts.first() {
+        let first = first.to_ascii_lowercase();
+
+        if let Some(rest) = first.strip_prefix("from") {
+            let angle_str = rest.split(" at ").next().unwrap_or_default().trim();
+            angle = parse_angle(angle_str).ok_or_else(|| {
+                Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("Invalid conic gradient angle: {}", angle_str),
+                )
+            })?;
+            parts.remove(0);
+        } else if first.starts_with("at ") {
+            parts.remove(0);
+        }
+    }
+
+    let gradient_stops = parse_color_stops(&parts, is_active)?;
+    let radians = angle.to_radians();
+
+    let direction = GradientCoordinates {
+        start: [0.5, 0.5],
+        end: [0.5 + 0.5 * radians.sin(), 0.5 - 0.5 * radians.cos()],
+    };
+
+    Ok(Color::Gradient(Gradient::new(
+        direction,
+        gradient_stops,
+        GradientShape::Conic,
+    )))
+}
+
+/// Parses a list of CSS color stops, such as `#f00 20%`, into gradient stops.
+///
+/// Stops with an inline percentage use it as their position. As in CSS, the first and last stops
+/// default to 0% and 100%, other stops without a position are spaced evenly between the
+/// surrounding positioned stops, and a position smaller than a previous one is raised to it.
+///
+/// A bare percentage between two colors is a midpoint hint, which moves the point where the two
+/// colors are mixed equally. It is approximated by a synthetic stop at the hint's position with
+/// the color halfway between its neighbours. Without a hint the midpoint lies halfway between the
+/// stops.
+///
+/// # Arguments
+///
+/// - `parts`: The color stop strings, one per stop.
+/// - `is_active`: An optional flag indicating whether the color is active.
+///
+/// # Returns
+///
+/// - `Ok(Vec<D2D1_GRADIENT_STOP>)`: The gradient stops, with positions from 0.0 to 1.0.
+/// - `Err(Error)`: An error if any color or position is invalid or not solid, a hint is not
+///   between two colors, or fewer than two colors are given.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// // The middle stop is placed halfway between 20% and 100%, at 60%.
+/// let stops = parse_color_stops(&["#f00 20%", "#0f0", "#00f"], None)?;
+/// // Red and blue are mixed equally at 30% instead of 50%.
+/// let stops = parse_color_stops(&["#f00", "30%", "#00f"], None)?;
+/// ```
+fn parse_color_stops(parts: &[&str], is_active: Option<bool>) -> Result<Vec<D2D1_GRADIENT_STOP>> {
+    let mut colors = Vec::with_capacity(parts.len());
+    let mut positions = Vec::with_capacity(parts.len());
+    // The midpoint hint following each color, if any.
+    let mut hints: Vec<Option<f32>> = Vec::with_capacity(parts.len());
+
+    for part in parts {
+        if let Some(hint) = parse_midpoint_hint(part) {
+            match hints.last_mut() {
+                Some(slot @ None) => *slot = Some(hint?),
+                _ => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("A midpoint hint must follow a color: {}", part),
+                    ))
+                }
+            }
+            continue;
+        }
+
+        let (color, position) = split_stop_position(part);
+
+        match parse_color_string(color, is_active)? {
+            Color::Solid(solid) => colors.push(solid.effective_color()),
+            Color::Gradient(_) => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("Invalid gradient stop: {}", part),
+                ))
+            }
+        }
+
+        positions.push(position.transpose()?);
+        hints.push(None);
+    }
+
+    if colors.len() < 2 {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "A gradient requires at least two colors",
+        ));
+    }
+
+    if let Some(Some(hint)) = hints.last() {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("A midpoint hint must precede a color: {}%", hint * 100.0),
+        ));
+    }
+
+    let last = positions.len() - 1;
+    positions[0] = positions[0].or(Some(0.0));
+    positions[last] = positions[last].or(Some(1.0));
+
+    // Fill each run of stops without a position by interpolating between its anchors.
+    let mut anchor = 0;
+    for i in 1..positions.len() {
+        let Some(position) = positions[i] else {
+            continue;
+        };
+
+        let from = positions[anchor].unwrap_or_default().min(position);
+        let step = (position - from) / (i - anchor) as f32;
+        for (j, slot) in positions.iter_mut().enumerate().take(i).skip(anchor + 1) {
+            *slot = Some(from + step * (j - anchor) as f32);
+        }
+
+        anchor = i;
+    }
+
+    let mut max_position = 0.0_f32;
+
+    let stops: Vec<D2D1_GRADIENT_STOP> = colors
+        .into_iter()
+        .zip(positions)
+        .map(|(color, position)| {
+            max_position = max_position.max(position.unwrap_or_default());
+            D2D1_GRADIENT_STOP {
+                position: max_position,
+                color,
+            }
+        })
+        .collect();
+
+    let mut result = Vec::with_capacity(stops.len() + hints.len());
+    for (i, stop) in stops.iter().enumerate() {
+        result.push(*stop);
+
+        if let (Some(hint), Some(next)) = (hints[i], stops.get(i + 1)) {
+            result.push(D2D1_GRADIENT_STOP {
+                position: hint.clamp(stop.position, next.position),
+                color: lerp_color(&stop.color, &next.color, 0.5),
+            });
+        }
+    }
+
+    Ok(result)
+}
+
+/// Parses a bare percentage color stop, such as `30%`, as a midpoint hint.
+///
+/// # Returns
+///
+/// `None` if the part is not a bare percentage, or else its position parsed into the range 0.0
+/// to 1.0 or an `ErrorKind::InvalidData` error if it lies outside 0% to 100%.
+fn parse_midpoint_hint(part: &str) -> Option<Result<f32>> {
+    let part = part.trim();
+    let value = part
+        .strip_suffix('%')?
+        .trim()
+        .parse::<f32>()
+        .ok()
+        .filter(|value| value.is_finite())?;
+
+    if !(0.0..=100.0).contains(&value) {
+        return Some(Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("Midpoint hint out of range: {}", part),
+        )));
+    }
+
+    Some(Ok(value / 100.0))
+}
+
+/// Splits a CSS color stop into its color and optional percentage position.
+///
+/// # Returns
+///
+/// The color string and, if the stop ends with a percentage, its position parsed into the range
+/// 0.0 to 1.0 or an `ErrorKind::InvalidData` error if it is not a valid number.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// let (color, position) = split_stop_position("rgb(255, 0, 0) 20%");
+/// assert_eq!(color, "rgb(255, 0, 0)");
+/// assert_eq!(position, Some(Ok(0.2)));
+/// ```
+fn split_stop_position(part: &str) -> (&str, Option<Result<f32>>) {
+    let part = part.trim();
+
+    let Some((color, position)) = part.rsplit_once(char::is_whitespace) else {
+        return (part, None);
+    };
+
+    let Some(percentage) = position.strip_suffix('%') else {
+        return (part, None);
+    };
+
+    let position = percentage
+        .parse::<f32>()
+        .ok()
+        .filter(|value| value.is_finite())
+        .map(|value| value / 100.0)
+        .ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("Invalid gradient stop position: {}", position),
+            )
+        });
+
+    (color.trim_end(), Some(position))
+}
+
+/// Parses a `to <side>` or `to <side> <side>` keyword direction into `GradientCoordinates`.
+///
+/// Sides point the gradient at the middle of an edge, and two sides point it from one corner of
+/// the box to the opposite corner. The sides may be given in either order and are matched
+/// case-insensitively.
+///
+/// # Returns
+///
+/// - `None`: If `s` does not start with the `to` keyword.
+/// - `Some(Ok(GradientCoordinates))`: The normalized coordinates for the direction.
+/// - `Some(Err(Error))`: An `ErrorKind::InvalidGradientCoordinates` error if the sides are
+///   unknown, repeated or conflicting.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// let coordinates = parse_direction_keyword("to bottom right").unwrap()?;
+/// assert_eq!(coordinates.start, [0.0, 0.0]);
+/// assert_eq!(coordinates.end, [1.0, 1.0]);
+/// ```
+fn parse_direction_keyword(s: &str) -> Option<Result<GradientCoordinates>> {
+    let s = s.trim().to_ascii_lowercase();
+    let sides = s.strip_prefix("to")?;
+
+    if !sides.starts_with(char::is_whitespace) {
+        return None;
+    }
+
+    let invalid = || {
+        Error::new(
+            ErrorKind::InvalidGradientCoordinates,
+            format!("Invalid gradient direction `{}`", s),
+        )
+    };
+
+    let mut x: Option<f32> = None;
+    let mut y: Option<f32> = None;
+
+    for side in sides.split_whitespace() {
+        let (axis, value) = match side {
+            "left" => (&mut x, -1.0),
+            "right" => (&mut x, 1.0),
+            "top" => (&mut y, -1.0),
+            "bottom" => (&mut y, 1.0),
+            _ => return Some(Err(invalid())),
+        };
+
+        if axis.replace(value).is_some() {
+            return Some(Err(invalid()));
+        }
+    }
+
+    if x.is_none() && y.is_none() {
+        return Some(Err(invalid()));
+    }
+
+    let (x, y) = (x.unwrap_or(0.0), y.unwrap_or(0.0));
+
+    Some(Ok(GradientCoordinates {
+        start: [0.5 - x * 0.5, 0.5 - y * 0.5],
+        end: [0.5 + x * 0.5, 0.5 + y * 0.5],
+    }))
+}
+
+/// Parses a CSS angle into degrees.
+///
+/// Supports the `deg`, `grad`, `rad` and `turn` units. The angle is not wrapped, so negative
+/// angles and angles beyond a full turn are returned as is.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// assert_eq!(parse_angle("0.25turn"), Some(90.0));
+/// ```
+fn parse_angle(s: &str) -> Option<f32> {
+    let s = s.trim().to_ascii_lowercase();
+
+    // `grad` must be checked before `rad` since it shares the suffix.
+    let (value, _, unit) = strip_string(&s, &[], &["deg", "grad", "rad", "turn"]);
+    let factor = match unit? {
+        "deg" => 1.0,
+        "grad" => 0.9,
+        "rad" => 180.0 / std::f32::consts::PI,
+        _ => 360.0,
+    };
+
+    value
+        .trim()
+        .parse::<f32>()
+        .ok()
+        .filter(|value| value.is_finite())
+        .map(|value| value * factor)
+}
+
+/// Returns the arguments of a CSS-like function call if `s` is a call to `name`.
+///
+/// The function name is matched case-insensitively and surrounding whitespace is ignored.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// assert_eq!(strip_function("radial-gradient(red, blue)", "radial-gradient"), Some("red, blue"));
+/// ```
+fn strip_function<'a>(s: &'a str, name: &str) -> Option<&'a str> {
+    let s = s.trim();
+    let (prefix, rest) = (s.get(..name.len())?, s.get(name.len()..)?);
+
+    if !prefix.eq_ignore_ascii_case(name) {
+        return None;
+    }
+
+    rest.trim_start()
+        .strip_prefix('(')?
+        .strip_suffix(')')
+        .map(str::trim)
+}
+
+/// Splits a string on commas that are not nested inside parentheses.
+///
+/// Each resulting part is trimmed of surrounding whitespace.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// assert_eq!(split_top_level("rgb(0, 0, 0), red"), vec!["rgb(0, 0, 0)", "red"]);
+/// ```
+fn split_top_level(s: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(s[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+
+    parts.push(s[start..].trim());
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ColorMappingImpl;
+    use crate::GradientImpl;
+    use crate::MAX_GRADIENT_STEPS;
+
+    #[test]
+    fn parser_functions_have_a_single_definition() {
+        // `mod parser` does not compile if both `parser.rs` and `parser/mod.rs` exist, and each
+        // path below must resolve to exactly one function with the canonical signature.
+        let _: fn(ColorMapping, Option<bool>) -> Result<Color> = crate::parser::parse_color_mapping;
+        let _: fn(&str, Option<bool>) -> Result<Color> = crate::parser::parse_color_string;
+        let colors = ["#f00".to_string(), "#00f".to_string()];
+        let stops = crate::parser::generate_gradient_stops(
+            &colors,
+            None,
+            GradientInterpolation::Linear,
+            None,
+        );
+        assert_eq!(stops.unwrap().len(), 2);
+        let _: fn(&GradientDirection) -> Result<GradientCoordinates> =
+            crate::parser::parse_gradient_direction;
+        let _: fn(&CssColor) -> Result<Color> = crate::parser::parse_solid_color;
+        let _: fn(&CssColor) -> Result<Color> = crate::parser::parse_gradient;
+    }
+
+    fn solid_color(s: &str) -> D2D1_COLOR_F {
+        match parse_color_string(s, None).unwrap() {
+            Color::Solid(solid) => solid.effective_color(),
+            Color::Gradient(_) => panic!("expected a solid color for {}", s),
+        }
+    }
+
+    #[test]
+    fn parses_short_hex_with_alpha() {
+        let color = solid_color("#f008");
+        assert_eq!((color.r, color.g, color.b), (1.0, 0.0, 0.0));
+        assert_eq!(color.a, 0x88 as f32 / 255.0);
+    }
+
+    #[test]
+    fn parses_long_hex_with_alpha() {
+        let color = solid_color("#89b4faCC");
+        assert_eq!(color.r, 0x89 as f32 / 255.0);
+        assert_eq!(color.g, 0xb4 as f32 / 255.0);
+        assert_eq!(color.b, 0xfa as f32 / 255.0);
+        assert_eq!(color.a, 0xcc as f32 / 255.0);
+    }
+
+    #[test]
+    fn rejects_seven_digit_hex() {
+        let error = parse_color_string("#89b4faC", None).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidHex);
+    }
+
+    #[test]
+    fn trims_whitespace_around_hex() {
+        assert_eq!(solid_color(" #89b4faCC\n"), solid_color("#89b4faCC"));
+        assert_eq!(classify_color(" #89b4faCC").unwrap(), ColorKind::Solid);
+
+        for s in [" #89b4faC", "\t#gg0000 "] {
+            assert_eq!(
+                parse_color_string(s, None).unwrap_err().kind(),
+                ErrorKind::InvalidHex,
+                "{:?}",
+                s
+            );
+            assert_eq!(
+                classify_color(s).unwrap_err().kind(),
+                ErrorKind::InvalidHex,
+                "{:?}",
+                s
+            );
+        }
+    }
+
+    #[test]
+    fn parses_radial_gradient_with_two_stops() {
+        for s in [
+            "radial-gradient(#ff0000, #0000ff)",
+            "radial-gradient(circle, #ff0000, #0000ff)",
+            "radial-gradient(ellipse closest-side at top left, #ff0000, #0000ff)",
+            "radial-gradient(circle 40px at 50% 50%, #ff0000, #0000ff)",
+        ] {
+            let color = parse_color_string(s, None).unwrap();
+            let gradient = color.as_gradient().unwrap();
+
+            assert_eq!(gradient.shape, GradientShape::Radial);
+            assert_eq!(gradient.gradient_stops.len(), 2);
+            assert_eq!(gradient.gradient_stops[0].color.r, 1.0);
+            assert_eq!(gradient.gradient_stops[1].color.b, 1.0);
+        }
+    }
+
+    #[test]
+    fn parses_conic_gradient_angle() {
+        let direction = |s| {
+            let color = parse_color_string(s, None).unwrap();
+            let gradient = color.as_gradient().unwrap();
+            assert_eq!(gradient.shape, GradientShape::Conic);
+            assert_eq!(gradient.direction.start, [0.5, 0.5]);
+            gradient.direction.end
+        };
+        let assert_close = |a: [f32; 2], b: [f32; 2]| {
+            assert!(
+                (a[0] - b[0]).abs() < 1e-6 && (a[1] - b[1]).abs() < 1e-6,
+                "{:?}",
+                a
+            );
+        };
+
+        assert_close(direction("conic-gradient(#ff0000, #0000ff)"), [0.5, 0.0]);
+        assert_close(
+            direction("conic-gradient(from 90deg, #ff0000, #0000ff)"),
+            [1.0, 0.5],
+        );
+        assert_close(
+            direction("conic-gradient(from 0.5turn at center, #ff0000, #0000ff)"),
+            [0.5, 1.0],
+        );
+    }
+
+    #[test]
+    fn parses_conic_gradient_stops() {
+        let color = parse_color_string(
+            "conic-gradient(from 45deg, #ff0000, #00ff00 25%, #0000ff)",
+            None,
+        )
+        .unwrap();
+        let positions: Vec<f32> = color
+            .as_gradient()
+            .unwrap()
+            .gradient_stops
+            .iter()
+            .map(|stop| stop.position)
+            .collect();
+
+        assert_eq!(positions, [0.0, 0.25, 1.0]);
+        assert!(
+            parse_color_string("conic-gradient(from sideways, #ff0000, #0000ff)", None).is_err()
+        );
+    }
+
+    #[test]
+    fn honors_explicit_stop_positions() {
+        for (s, expected) in [
+            (
+                "linear-gradient(90deg, #ff0000 20%, #0000ff 80%)",
+                vec![0.2, 0.8],
+            ),
+            (
+                "linear-gradient(90deg, #ff0000 20%, #00ff00, #0000ff 80%)",
+                vec![0.2, 0.5, 0.8],
+            ),
+            (
+                "linear-gradient(90deg, #ff0000, #00ff00, #ffffff 60%, #0000ff)",
+                vec![0.0, 0.3, 0.6, 1.0],
+            ),
+        ] {
+            let color = parse_color_string(s, None).unwrap();
+            let positions: Vec<f32> = color
+                .as_gradient()
+                .unwrap()
+                .gradient_stops
+                .iter()
+                .map(|stop| stop.position)
+                .collect();
+
+            assert_eq!(positions.len(), expected.len(), "{}", s);
+            for (actual, expected) in positions.iter().zip(&expected) {
+                assert!(
+                    (actual - expected).abs() < 1e-6,
+                    "{} gave {:?}",
+                    s,
+                    positions
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_radial_gradient_with_invalid_first_stop() {
+        for s in [
+            "radial-gradient(#gg0000, #ff0000, #0000ff)",
+            "radial-gradient(circle at nowhere, #ff0000, #0000ff)",
+        ] {
+            assert!(
+                parse_color_string(s, None).is_err(),
+                "{} should not parse",
+                s
+            );
+        }
+    }
+
+    #[test]
+    fn parses_functional_colors() {
+        assert_eq!(solid_color("rgb(255, 0, 0)").r, 1.0);
+        assert_eq!(solid_color("rgba(0, 0, 255, 0.5)").a, 0.5);
+        assert_eq!(solid_color("hsl(120, 100%, 50%)").g, 1.0);
+        assert_eq!(solid_color("hsla(240deg, 100%, 50%, 50%)").b, 1.0);
+    }
+
+    #[test]
+    fn space_and_comma_syntaxes_are_equal() {
+        for (space, comma) in [
+            ("rgb(255 0 0 / 50%)", "rgba(255, 0, 0, 0.5)"),
+            ("rgb(255 128 0)", "rgb(255, 128, 0)"),
+            ("rgba(0 0 255 / 0.25)", "rgba(0, 0, 255, 0.25)"),
+            ("hsl(120 100% 50% / 50%)", "hsla(120, 100%, 50%, 0.5)"),
+        ] {
+            assert_eq!(solid_color(space), solid_color(comma), "{}", space);
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_slash_syntax() {
+        for s in [
+            "rgb(255 0 0 /)",
+            "rgb(255 0 / 50%)",
+            "rgb(255 0 0 / 50% / 50%)",
+            "rgb(255, 0, 0 / 50%)",
+        ] {
+            let error = parse_color_string(s, None).unwrap_err();
+
+            assert_eq!(error.kind(), ErrorKind::InvalidRgb, "{}", s);
+        }
+    }
+
+    #[test]
+    fn rejects_out_of_range_functional_colors() {
+        for (s, component) in [
+            ("rgb(300, 0, 0)", "`300`"),
+            ("rgba(0, 0, 0, 2)", "`2`"),
+            ("hsl(0, 150%, 50%)", "`150%`"),
+        ] {
+            let error = parse_color_string(s, None).unwrap_err();
+            assert_eq!(error.kind(), ErrorKind::InvalidRgb);
+            assert!(error.to_string().contains(component), "{}", error);
+        }
+    }
+
+    #[test]
+    fn mapping_with_one_valid_color_is_solid() {
+        let mapping = ColorMapping::new(&["#ff0000", "#gg0000"], GradientDirection::from("90deg"));
+        let color = parse_color_mapping(mapping, None).unwrap();
+
+        assert_eq!(color.as_solid().unwrap().color.r, 1.0);
+    }
+
+    #[test]
+    fn mapping_spaces_surviving_colors_evenly() {
+        let mapping = ColorMapping::new(
+            &["#ff0000", "#gg0000", "#00ff00", "#0000ff"],
+            GradientDirection::from("90deg"),
+        );
+        let color = parse_color_mapping(mapping, None).unwrap();
+        let positions: Vec<f32> = color
+            .as_gradient()
+            .unwrap()
+            .gradient_stops
+            .iter()
+            .map(|stop| stop.position)
+            .collect();
+
+        assert_eq!(positions, [0.0, 0.5, 1.0]);
+    }
+
+    #[test]
+    fn mapping_with_coordinates_parses_into_gradient() {
+        let mapping =
+            ColorMapping::with_coordinates(&["#ff0000", "#0000ff"], [0.0, 0.25], [1.0, 0.75])
+                .unwrap();
+        let color = parse_color_mapping(mapping, None).unwrap();
+        let gradient = color.as_gradient().unwrap();
+
+        assert_eq!(gradient.direction.start, [0.0, 0.25]);
+        assert_eq!(gradient.direction.end, [1.0, 0.75]);
+        assert_eq!(gradient.gradient_stops.len(), 2);
+    }
+
+    #[test]
+    fn mapping_with_out_of_range_coordinates_is_an_error() {
+        for (start, end) in [([-0.1, 0.0], [1.0, 1.0]), ([0.0, 0.0], [1.0, 1.5])] {
+            let error = ColorMapping::with_coordinates(&["#ff0000"], start, end).unwrap_err();
+
+            assert_eq!(error.kind(), ErrorKind::InvalidGradientCoordinates);
+        }
+    }
+
+    #[test]
+    fn mapping_keeps_repeated_colors_at_distinct_positions() {
+        let stops = |positions: Vec<f32>| {
+            let mapping = ColorMapping {
+                positions: Some(positions),
+                ..ColorMapping::new(&["#fff", "#000", "#fff"], GradientDirection::from("90deg"))
+            };
+            let color = parse_color_mapping(mapping, None).unwrap();
+            color
+                .as_gradient()
+                .unwrap()
+                .gradient_stops
+                .iter()
+                .map(|stop| (stop.position, stop.color.r))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            stops(vec![0.0, 0.2, 1.0]),
+            [(0.0, 1.0), (0.2, 0.0), (1.0, 1.0)]
+        );
+        assert_eq!(
+            stops(vec![1.0, 0.2, 0.0]),
+            [(0.0, 1.0), (0.2, 0.0), (1.0, 1.0)]
+        );
+    }
+
+    #[test]
+    fn mapping_opacity_is_carried_through() {
+        let opacity = |json: &str| {
+            let mapping: ColorMapping = serde_json::from_str(json).unwrap();
+            let color = parse_color_mapping(mapping, None).unwrap();
+            color.as_gradient().unwrap().opacity
+        };
+
+        assert_eq!(
+            opacity(
+                r##"{ "colors": ["#ff0000", "#0000ff"], "direction": "to right", "opacity": 0.5 }"##
+            ),
+            0.5
+        );
+        assert_eq!(
+            opacity(
+                r##"{ "colors": ["#ff0000", "#0000ff"], "direction": "to right", "opacity": 2.0 }"##
+            ),
+            1.0
+        );
+        assert_eq!(
+            opacity(r##"{ "colors": ["#ff0000", "#0000ff"], "direction": "to right" }"##),
+            1.0
+        );
+    }
+
+    #[test]
+    fn mapping_opacity_applies_to_solids() {
+        let mapping = ColorMapping {
+            opacity: Some(-1.0),
+            ..ColorMapping::new(&["#ff0000"], GradientDirection::from("to right"))
+        };
+        let color = parse_color_mapping(mapping, None).unwrap();
+
+        assert_eq!(color.as_solid().unwrap().opacity, 0.0);
+    }
+
+    #[test]
+    fn mapping_with_a_nested_gradient_is_an_error() {
+        let nested = "linear-gradient(#ff0000, #0000ff)";
+        let mapping = ColorMapping::new(&["#ff0000", nested], GradientDirection::from("90deg"));
+        let error = parse_color_mapping(mapping, None).unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        assert!(error.message().contains("color 1"), "{}", error);
+        assert!(error.message().contains(nested), "{}", error);
+    }
+
+    #[test]
+    fn mapping_without_valid_colors_is_an_error() {
+        let mapping = ColorMapping::new(&["#gg0000", "#hh0000"], GradientDirection::from("90deg"));
+        let error = parse_color_mapping(mapping, None).unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn degenerate_css_gradient_is_finite() {
+        let red = D2D1_COLOR_F {
+            r: 1.0,
+            g: 0.0,
+            b: 0.0,
+            a: 1.0,
+        };
+        let direction = || GradientCoordinates {
+            start: [0.0, 0.5],
+            end: [1.0, 0.5],
+        };
+
+        let color = evenly_spaced_gradient(vec![red], direction()).unwrap();
+        let solid = color.as_solid().unwrap().color;
+        assert!([solid.r, solid.g, solid.b, solid.a]
+            .iter()
+            .all(|c| c.is_finite()));
+        assert_eq!(solid, red);
+
+        let error = evenly_spaced_gradient(Vec::new(), direction()).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+
+        let color = evenly_spaced_gradient(vec![red, red], direction()).unwrap();
+        let positions: Vec<f32> = color
+            .as_gradient()
+            .unwrap()
+            .gradient_stops
+            .iter()
+            .map(|stop| stop.position)
+            .collect();
+        assert_eq!(positions, [0.0, 1.0]);
+    }
+
+    #[test]
+    fn parses_angle_units_into_coordinates() {
+        let to_top = ([0.5, 1.0], [0.5, 0.0]);
+        let to_right = ([0.0, 0.5], [1.0, 0.5]);
+
+        for (angle, (start, end)) in [
+            ("0deg", to_top),
+            ("90deg", to_right),
+            ("0.25turn", to_right),
+            ("100grad", to_right),
+            ("1.5708rad", to_right),
+            ("-270deg", to_right),
+            ("450deg", to_right),
+        ] {
+            let coordinates = parse_gradient_direction(&GradientDirection::from(angle)).unwrap();
+            let actual = coordinates.start.iter().chain(&coordinates.end);
+            let expected = start.iter().chain(&end);
+
+            for (a, b) in actual.zip(expected) {
+                assert!((a - b).abs() < 1e-4, "{} gave {:?}", angle, coordinates);
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_angle_units() {
+        let error = parse_gradient_direction(&GradientDirection::from("90degs")).unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::InvalidGradientCoordinates);
+        assert!(error.to_string().contains("90degs"), "{}", error);
+    }
+
+    #[test]
+    fn parses_keyword_directions() {
+        for (keyword, start, end) in [
+            ("to top", [0.5, 1.0], [0.5, 0.0]),
+            ("to bottom", [0.5, 0.0], [0.5, 1.0]),
+            ("to left", [1.0, 0.5], [0.0, 0.5]),
+            ("to right", [0.0, 0.5], [1.0, 0.5]),
+            ("to top left", [1.0, 1.0], [0.0, 0.0]),
+            ("to top right", [0.0, 1.0], [1.0, 0.0]),
+            ("to bottom left", [1.0, 0.0], [0.0, 1.0]),
+            ("to bottom right", [0.0, 0.0], [1.0, 1.0]),
+        ] {
+            let coordinates = parse_gradient_direction(&GradientDirection::from(keyword)).unwrap();
+
+            assert_eq!(coordinates.start, start, "{}", keyword);
+            assert_eq!(coordinates.end, end, "{}", keyword);
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_keyword_directions() {
+        for keyword in ["to middle", "to left right", "to top top", "to"] {
+            let error = parse_gradient_direction(&GradientDirection::from(keyword)).unwrap_err();
+
+            assert_eq!(
+                error.kind(),
+                ErrorKind::InvalidGradientCoordinates,
+                "{}",
+                keyword
+            );
+        }
+    }
+
+    #[test]
+    fn non_linear_interpolation_adds_stops() {
+        let colors = ["#ff0000", "#00ff00", "#0000ff"].map(String::from);
+        let stop_count = |interpolation| {
+            generate_gradient_stops(&colors, None, interpolation, None)
+                .unwrap()
+                .len()
+        };
+
+        assert_eq!(stop_count(GradientInterpolation::Linear), 3);
+        assert_eq!(
+            stop_count(GradientInterpolation::EaseInOut),
+            3 + 2 * (EASE_IN_OUT_SEGMENTS as usize - 1)
+        );
+        assert_eq!(stop_count(GradientInterpolation::Steps(4)), 3 + 2 * 2 * 3);
+    }
+
+    #[test]
+    fn rejects_step_counts_above_the_maximum() {
+        let colors = ["#ff0000", "#0000ff"].map(String::from);
+        let generate = |steps| {
+            generate_gradient_stops(&colors, None, GradientInterpolation::Steps(steps), None)
+        };
+
+        assert_eq!(
+            generate(MAX_GRADIENT_STEPS).unwrap().len(),
+            2 + 2 * (MAX_GRADIENT_STEPS as usize - 1)
+        );
+        for steps in [MAX_GRADIENT_STEPS + 1, u32::MAX] {
+            assert_eq!(generate(steps).unwrap_err().kind(), ErrorKind::InvalidData);
+        }
+
+        let mapping: ColorMapping = serde_json::from_str(
+            r##"{
+                "colors": ["#ff0000", "#0000ff"],
+                "direction": "to right",
+                "interpolation": { "steps": 4294967295 }
+            }"##,
+        )
+        .unwrap();
+
+        assert_eq!(
+            mapping.validate().unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+        assert_eq!(
+            parse_color_mapping(mapping, None).unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+    }
+
+    #[test]
+    fn ease_in_out_keeps_stops_sorted_and_ends_fixed() {
+        let colors = ["#000000", "#ffffff"].map(String::from);
+        let stops =
+            generate_gradient_stops(&colors, None, GradientInterpolation::EaseInOut, None).unwrap();
+
+        assert!(stops
+            .windows(2)
+            .all(|pair| pair[0].position <= pair[1].position));
+        assert_eq!(stops.first().unwrap().color.r, 0.0);
+        assert_eq!(stops.last().unwrap().color.r, 1.0);
+        // Easing starts slower than a linear blend.
+        assert!(stops[1].color.r < stops[1].position);
+    }
+
+    #[test]
+    fn parses_registered_named_colors_case_insensitively() {
+        let mauve = D2D1_COLOR_F {
+            r: 0.796,
+            g: 0.651,
+            b: 0.969,
+            a: 1.0,
+        };
+        register_named_color("test-mauve", mauve);
+
+        assert_eq!(solid_color("test-mauve"), mauve);
+        assert_eq!(solid_color(" Test-Mauve "), mauve);
+    }
+
+    #[test]
+    fn unknown_names_are_invalid_input() {
+        let error = parse_color_string("not-a-registered-color", None).unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn current_color_resolves_to_the_context() {
+        let context = parse_color_string("linear-gradient(90deg, #ff0000, #0000ff)", None).unwrap();
+
+        for s in ["currentColor", "CURRENTCOLOR", " currentcolor "] {
+            assert_eq!(
+                parse_color_with_context(s, &context, None).unwrap(),
+                context
+            );
+        }
+    }
+
+    #[test]
+    fn other_strings_ignore_the_context() {
+        let context = parse_color_string("#ff0000", None).unwrap();
+        let color = parse_color_with_context("#0000ff", &context, None).unwrap();
+
+        assert_eq!(color, parse_color_string("#0000ff", None).unwrap());
+        assert!(parse_color_with_context("currentColour", &context, None).is_err());
+    }
+
+    #[test]
+    fn classifies_colors_by_shape() {
+        for (s, kind) in [
+            ("#89b4fa", ColorKind::Solid),
+            ("rgb(255, 0, 0)", ColorKind::Solid),
+            ("accent", ColorKind::Solid),
+            ("dwm:ColorizationColor", ColorKind::Solid),
+            (
+                "linear-gradient(90deg, #ff0000, #0000ff)",
+                ColorKind::LinearGradient,
+            ),
+            (
+                "repeating-radial-gradient(#ff0000, #0000ff 25%)",
+                ColorKind::RadialGradient,
+            ),
+            (
+                "conic-gradient(from 45deg, #ff0000, #0000ff)",
+                ColorKind::ConicGradient,
+            ),
+        ] {
+            assert_eq!(classify_color(s).unwrap(), kind, "{}", s);
+        }
+    }
+
+    #[test]
+    fn classifying_invalid_colors_fails_like_parsing() {
+        for s in [
+            "#gg0000",
+            "#12345",
+            "not-a-color",
+            "rgb(300, 0, 0)",
+            "cmyk(0%, 0%, 0%)",
+            "oklch(0.5 0.1)",
+            "mix(#ff0000, #0000ff)",
+            "color-mix(in lab, #ff0000, #0000ff)",
+            "linear-gradient(to sideways, #ff0000, #0000ff)",
+            "radial-gradient(#ff0000)",
+            "conic-gradient(from sideways, #ff0000, #0000ff)",
+        ] {
+            assert_eq!(
+                classify_color(s).unwrap_err().kind(),
+                parse_color_string(s, None).unwrap_err().kind(),
+                "{}",
+                s
+            );
+        }
+    }
+
+    #[test]
+    fn parse_or_returns_the_parsed_color() {
+        let fallback = Color::transparent();
+        let color = parse_color_string_or("#ff0000", fallback, None);
+
+        assert_eq!(color, parse_color_string("#ff0000", None).unwrap());
+    }
+
+    #[test]
+    fn parse_or_falls_back_on_errors() {
+        let fallback = parse_color_string("#89b4fa", None).unwrap();
+
+        for s in [
+            "#gg0000",
+            "rgb(300, 0, 0)",
+            "linear-gradient(sideways, #ff0000)",
+        ] {
+            assert_eq!(
+                parse_color_string_or(s, fallback.clone(), None),
+                fallback,
+                "{}",
+                s
+            );
+        }
+    }
+
+    #[test]
+    fn parses_cmyk_colors() {
+        for (s, expected) in [
+            ("cmyk(100%, 0%, 0%, 0%)", (0.0, 1.0, 1.0)),
+            ("cmyk(0%, 100%, 0%, 0%)", (1.0, 0.0, 1.0)),
+            ("cmyk(0%, 0%, 100%, 0%)", (1.0, 1.0, 0.0)),
+            ("cmyk(0%, 0%, 0%, 100%)", (0.0, 0.0, 0.0)),
+            ("cmyk(0%, 100%, 100%, 0%)", (1.0, 0.0, 0.0)),
+            ("cmyk(0.5, 0%, 25%, 50%)", (0.25, 0.5, 0.375)),
+        ] {
+            let color = solid_color(s);
+
+            assert_eq!(
+                (color.r, color.g, color.b, color.a),
+                (expected.0, expected.1, expected.2, 1.0),
+                "{}",
+                s
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_cmyk_components() {
+        for (s, token) in [
+            ("cmyk(0%, 120%, 0%, 0%)", "120%"),
+            ("cmyk(0%, 0%, cyan, 0%)", "cyan"),
+            ("cmyk(0%, 0%, 0%)", "0%, 0%, 0%"),
+        ] {
+            let error = parse_color_string(s, None).unwrap_err();
+
+            assert_eq!(error.kind(), ErrorKind::InvalidInput, "{}", s);
+            assert!(error.to_string().contains(token), "{}", error);
+        }
+    }
+
+    #[test]
+    fn every_supported_format_parses_a_sample() {
+        register_named_color("test-format", D2D1_COLOR_F::default());
+        assert!(!supported_formats().is_empty());
+
+        for format in supported_formats() {
+            let sample = match *format {
+                "hex" => "#89b4fa",
+                "transparent" | "none" | "accent" | "accent_dark" | "accent_light" => format,
+                "dwm:" => "dwm:ColorizationColor",
+                "rgb()" => "rgb(255, 0, 0)",
+                "rgba()" => "rgba(255, 0, 0, 0.5)",
+                "hsl()" => "hsl(120, 100%, 50%)",
+                "hsla()" => "hsla(120, 100%, 50%, 0.5)",
+                "cmyk()" => "cmyk(0%, 100%, 100%, 0%)",
+                "oklab()" => "oklab(0.5 0.1 -0.1)",
+                "oklch()" => "oklch(0.5 0.1 120)",
+                "mix()" => "mix(#ff0000, #0000ff, 0.5)",
+                "color-mix()" => "color-mix(in srgb, #ff0000, #0000ff)",
+                "linear-gradient()" => "linear-gradient(90deg, #ff0000, #0000ff)",
+                "repeating-linear-gradient()" => "repeating-linear-gradient(#ff0000, #0000ff 25%)",
+                "radial-gradient()" => "radial-gradient(#ff0000, #0000ff)",
+                "repeating-radial-gradient()" => "repeating-radial-gradient(#ff0000, #0000ff 25%)",
+                "conic-gradient()" => "conic-gradient(#ff0000, #0000ff)",
+                "repeating-conic-gradient()" => "repeating-conic-gradient(#ff0000, #0000ff 25%)",
+                "named" => "test-format",
+                _ => panic!("no sample for the supported format `{}`", format),
+            };
+
+            // The accent and DWM colors depend on the system, so only their syntax is checked.
+            if matches!(*format, "accent" | "accent_dark" | "accent_light" | "dwm:") {
+                assert_eq!(
+                    classify_color(sample).unwrap(),
+                    ColorKind::Solid,
+                    "{}",
+                    sample
+                );
+            } else {
+                assert!(parse_color_string(sample, None).is_ok(), "{}", sample);
+            }
+        }
+    }
+
+    #[test]
+    fn transparent_keyword_has_zero_alpha() {
+        for s in ["transparent", "Transparent", " TRANSPARENT "] {
+            let color = parse_color_string(s, None).unwrap();
+            let solid = color.as_solid().unwrap();
+
+            assert_eq!(solid.color.a, 0.0, "{}", s);
+            assert_eq!(
+                (solid.color.r, solid.color.g, solid.color.b),
+                (0.0, 0.0, 0.0)
+            );
+            assert!(!solid.none);
+        }
+    }
+
+    #[test]
+    fn mixes_solid_colors() {
+        let red = solid_color("#ff0000");
+        let blue = solid_color("#0000ff");
+
+        assert_eq!(solid_color("mix(#ff0000, #0000ff, 0%)"), red);
+        assert_eq!(solid_color("mix(#ff0000, #0000ff, 100%)"), blue);
+
+        let midpoint = solid_color("mix(#ff0000, #0000ff, 50%)");
+        assert_eq!(
+            (midpoint.r, midpoint.g, midpoint.b, midpoint.a),
+            (0.5, 0.0, 0.5, 1.0)
+        );
+    }
+
+    #[test]
+    fn rejects_gradients_and_bad_amounts_in_mix() {
+        for s in [
+            "mix(linear-gradient(90deg, #ff0000, #0000ff), #0000ff, 50%)",
+            "mix(#ff0000, #0000ff, half)",
+            "mix(#ff0000, #0000ff)",
+        ] {
+            let error = parse_color_string(s, None).unwrap_err();
+
+            assert_eq!(error.kind(), ErrorKind::InvalidInput, "{}", s);
+        }
+    }
+
+    #[test]
+    fn color_mix_weighs_the_colors() {
+        for (s, (r, b)) in [
+            ("color-mix(in srgb, #ff0000, #0000ff)", (0.5, 0.5)),
+            ("color-mix(in srgb, #ff0000 40%, #0000ff)", (0.4, 0.6)),
+            ("color-mix(in srgb, #ff0000, #0000ff 75%)", (0.25, 0.75)),
+            ("color-mix(in sRGB, #ff0000 20%, #0000ff 20%)", (0.5, 0.5)),
+            ("color-mix(in srgb, #ff0000 30%, #0000ff 10%)", (0.75, 0.25)),
+        ] {
+            let color = solid_color(s);
+
+            assert!((color.r - r).abs() < 1e-6, "{} gave {:?}", s, color);
+            assert!((color.b - b).abs() < 1e-6, "{} gave {:?}", s, color);
+            assert_eq!(color.g, 0.0);
+        }
+    }
+
+    #[test]
+    fn color_mix_rejects_unsupported_input() {
+        for s in [
+            "color-mix(in oklch, #ff0000, #0000ff)",
+            "color-mix(#ff0000, #0000ff)",
+            "color-mix(in srgb, #ff0000 0%, #0000ff 0%)",
+            "color-mix(in srgb, #ff0000 150%, #0000ff)",
+        ] {
+            let error = parse_color_string(s, None).unwrap_err();
+
+            assert_eq!(error.kind(), ErrorKind::InvalidInput, "{}", s);
+        }
+    }
+
+    #[test]
+    fn midpoint_hint_moves_the_even_mix() {
+        let even = parse_color_string("linear-gradient(#ff0000, #0000ff)", None).unwrap();
+        let hinted = parse_color_string("linear-gradient(#ff0000, 30%, #0000ff)", None).unwrap();
+        let (even, hinted) = (even.as_gradient().unwrap(), hinted.as_gradient().unwrap());
+
+        assert_eq!(even.gradient_stops.len(), 2);
+        assert_eq!(hinted.gradient_stops.len(), 3);
+        assert!((hinted.gradient_stops[1].position - 0.3).abs() < 1e-6);
+
+        // Without a hint red and blue are mixed equally at 50%, with it at 30%.
+        let halfway = lerp_color(&solid_color("#ff0000"), &solid_color("#0000ff"), 0.5);
+        assert_eq!(even.color_at(0.5), halfway);
+        assert_eq!(hinted.color_at(0.3), halfway);
+        assert!(hinted.color_at(0.5).b > even.color_at(0.5).b);
+    }
+
+    #[test]
+    fn rejects_misplaced_midpoint_hints() {
+        for s in [
+            "linear-gradient(30%, #ff0000, #0000ff)",
+            "linear-gradient(#ff0000, #0000ff, 30%)",
+            "linear-gradient(#ff0000, 30%, 40%, #0000ff)",
+            "linear-gradient(#ff0000, 130%, #0000ff)",
+        ] {
+            assert!(parse_color_string(s, None).is_err(), "{}", s);
+        }
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn dispatches_dwm_references() {
+        assert_eq!(
+            strip_dwm_reference(" DWM: ColorizationColor "),
+            Some("ColorizationColor")
+        );
+        assert_eq!(strip_dwm_reference("dwm"), None);
+        assert_eq!(strip_dwm_reference("#ff0000"), None);
+        assert_eq!(
+            classify_color("dwm:ColorizationColor").unwrap(),
+            ColorKind::Solid
+        );
+
+        // The packed DWORD is mocked here instead of read from the registry.
+        let expected = D2D1_COLOR_F {
+            r: 0x11 as f32 / 255.0,
+            g: 0x22 as f32 / 255.0,
+            b: 0x33 as f32 / 255.0,
+            a: 1.0,
+        };
+        assert_eq!(unpack_dwm_color("ColorizationColor", 0x80112233), expected);
+        assert_eq!(unpack_dwm_color("accentcolor", 0x80332211), expected);
+
+        let error = parse_color_string("dwm:WinColorMissingValue", None).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidAccent);
+    }
+
+    #[test]
+    fn parses_layered_gradients() {
+        let layers = parse_color_layers(
+            "linear-gradient(90deg, rgba(255, 0, 0, 0.5), #0000ff), linear-gradient(to bottom, #00ff00 20%, #ffffff)",
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(layers.len(), 2);
+        let first = layers[0].as_gradient().unwrap();
+        assert_eq!(first.gradient_stops[0].color.a, 0.5);
+        assert_eq!(first.gradient_stops[1].color, solid_color("#0000ff"));
+        let second = layers[1].as_gradient().unwrap();
+        assert_eq!(second.gradient_stops[0].position, 0.2);
+        assert_eq!(second.gradient_stops[1].color, solid_color("#ffffff"));
+    }
+
+    #[test]
+    fn parses_a_solid_and_a_gradient_layer() {
+        let layers =
+            parse_color_layers("rgb(255, 0, 0), radial-gradient(#ff0000, #0000ff)", None).unwrap();
+
+        assert_eq!(layers.len(), 2);
+        assert_eq!(layers[0].as_solid().unwrap().color, solid_color("#ff0000"));
+        assert_eq!(
+            layers[1].as_gradient().unwrap().shape,
+            GradientShape::Radial
+        );
+    }
+
+    #[test]
+    fn rejects_empty_and_invalid_layers() {
+        for s in ["#ff0000, , #0000ff", "#ff0000,", "#ff0000, #gg0000"] {
+            assert!(parse_color_layers(s, None).is_err(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn oklab_colors_match_their_srgb_hex() {
+        for (s, hex) in [
+            ("oklch(0.627955 0.257683 29.2339)", "#ff0000"),
+            ("oklch(62.7955% 0.257683 29.2339deg)", "#ff0000"),
+            ("oklab(0.627955 0.224863 0.125846)", "#ff0000"),
+            ("oklch(0.519752 0.176858 142.495)", "#008000"),
+            ("oklch(0.452014 0.313214 264.052 / 50%)", "#0000ff80"),
+            ("oklab(1 0 0)", "#ffffff"),
+        ] {
+            let (color, expected) = (solid_color(s), solid_color(hex));
+
+            for (actual, expected) in [
+                (color.r, expected.r),
+                (color.g, expected.g),
+                (color.b, expected.b),
+                (color.a, expected.a),
+            ] {
+                assert!(
+                    (actual - expected).abs() <= 1.0 / 255.0,
+                    "{} gave {:?}, expected {}",
+                    s,
+                    color,
+                    hex
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn out_of_gamut_oklch_is_clamped() {
+        let color = solid_color("oklch(0.9 0.4 150)");
+
+        for channel in [color.r, color.g, color.b] {
+            assert!((0.0..=1.0).contains(&channel), "{:?}", color);
+        }
+        assert_eq!(color.r, 0.0);
+    }
+
+    #[test]
+    fn rejects_malformed_oklab_components() {
+        for s in [
+            "oklch(0.5 0.1)",
+            "oklch(0.5 0.1 30 40)",
+            "oklch(0.5 -0.1 30)",
+            "oklch(0.5 0.1 30turns)",
+            "oklab(0.5 x 0.1)",
+            "oklab(0.5 0.1 0.1 / abc)",
+        ] {
+            let error = parse_color_string(s, None).unwrap_err();
+
+            assert_eq!(error.kind(), ErrorKind::InvalidInput, "{}", s);
+        }
+    }
 }
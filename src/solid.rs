@@ -1,6 +1,234 @@
+use crate::error::Error;
+use crate::error::ErrorKind;
+use crate::error::Result;
+use crate::parser::parse_hex_color;
+use crate::utils::channel_to_u8;
+use crate::utils::d2d1_to_hsv;
+use crate::utils::hsv_to_d2d1;
+use crate::utils::premultiply;
+use crate::utils::srgb_to_linear;
+use crate::utils::unpremultiply;
+use crate::utils::validate_color;
+use crate::utils::with_alpha;
+use crate::utils::Hsva;
+use crate::Gradient;
+use crate::GradientCoordinates;
+use crate::GradientShape;
+use windows::Foundation::Numerics::Matrix3x2;
 use windows::Win32::Graphics::Direct2D::Common::D2D1_COLOR_F;
+use windows::Win32::Graphics::Direct2D::Common::D2D1_GRADIENT_STOP;
+use windows::Win32::Graphics::Direct2D::ID2D1RenderTarget;
 use windows::Win32::Graphics::Direct2D::ID2D1SolidColorBrush;
 
+pub trait SolidImpl {
+    /// Creates a `Solid` from a Win32 `COLORREF` value.
+    ///
+    /// A `COLORREF` is packed as `0x00BBGGRR`. Since it carries no alpha, the resulting color is
+    /// fully opaque.
+    ///
+    /// # Parameters
+    /// - `colorref`: The packed `COLORREF` value.
+    ///
+    /// # Returns
+    /// A new `Solid` with no associated brush.
+    fn from_colorref(colorref: u32) -> Solid;
+
+    /// Converts the color into a Win32 `COLORREF` value.
+    ///
+    /// Each channel is rounded to the nearest 8-bit value and packed as `0x00BBGGRR`. The alpha
+    /// channel is dropped.
+    ///
+    /// # Returns
+    /// The packed `COLORREF` value.
+    fn to_colorref(&self) -> u32;
+
+    /// Creates a `Solid` from 8-bit RGBA channels.
+    ///
+    /// # Parameters
+    /// - `r`, `g`, `b`, `a`: The red, green, blue and alpha channels, ranging from 0 to 255.
+    ///
+    /// # Returns
+    /// A new `Solid` with each channel normalized to 0.0 to 1.0 and no associated brush.
+    fn from_rgba8(r: u8, g: u8, b: u8, a: u8) -> Solid;
+
+    /// Creates a `Solid` from a hex color string without going through the CSS parser.
+    ///
+    /// Supports the `#RGB`, `#RGBA`, `#RRGGBB` and `#RRGGBBAA` forms. Without an alpha component
+    /// the color is fully opaque.
+    ///
+    /// # Parameters
+    /// - `hex`: The hex color, including the leading `#`.
+    ///
+    /// # Returns
+    /// A `Result` containing the `Solid` with no associated brush, or an `ErrorKind::InvalidHex`
+    /// error if the string is malformed.
+    fn from_hex(hex: &str) -> Result<Solid>;
+
+    /// Converts the color into 8-bit RGBA channels.
+    ///
+    /// Each channel is rounded to the nearest value rather than truncated, so 0.5 becomes 128.
+    ///
+    /// # Returns
+    /// A `(r, g, b, a)` tuple with each channel ranging from 0 to 255.
+    fn to_rgba8(&self) -> (u8, u8, u8, u8);
+
+    /// Returns the color with `opacity` multiplied into its alpha, which is the color the brush
+    /// draws.
+    ///
+    /// # Returns
+    /// The `D2D1_COLOR_F` with an alpha of `color.a * opacity`.
+    fn effective_color(&self) -> D2D1_COLOR_F;
+
+    /// Creates a `Solid` from 8-bit channels in BGRA order, as used by GDI and many Win32 APIs.
+    ///
+    /// # Parameters
+    /// - `b`, `g`, `r`, `a`: The blue, green, red and alpha channels, ranging from 0 to 255.
+    ///
+    /// # Returns
+    /// A new `Solid` with each channel normalized to 0.0 to 1.0 and no associated brush.
+    fn from_bgra8(b: u8, g: u8, r: u8, a: u8) -> Solid;
+
+    /// Converts the color into 8-bit channels in BGRA order.
+    ///
+    /// Each channel is rounded to the nearest value, as with `to_rgba8`.
+    ///
+    /// # Returns
+    /// A `(b, g, r, a)` tuple with each channel ranging from 0 to 255.
+    fn to_bgra8(&self) -> (u8, u8, u8, u8);
+
+    /// Computes the relative luminance of the color as defined by WCAG.
+    ///
+    /// The sRGB channels are linearized before being weighted, and the alpha channel is ignored.
+    ///
+    /// # Returns
+    /// The relative luminance, ranging from 0.0 (black) to 1.0 (white).
+    fn relative_luminance(&self) -> f32;
+
+    /// Creates a `Solid` from HSV channels.
+    ///
+    /// # Parameters
+    /// - `h`: The hue in degrees. Values outside 0.0 to 360.0 are wrapped.
+    /// - `s`: The saturation as a percentage, clamped to 0.0 to 100.0.
+    /// - `v`: The value (brightness) as a percentage, clamped to 0.0 to 100.0.
+    /// - `a`: The alpha, ranging from 0.0 to 1.0.
+    ///
+    /// # Returns
+    /// A new `Solid` with no associated brush.
+    fn with_hsv(h: f32, s: f32, v: f32, a: f32) -> Solid;
+
+    /// Converts the color into HSV channels.
+    ///
+    /// # Returns
+    /// A `(h, s, v, a)` tuple with the hue in degrees ranging from 0.0 to 360.0, the saturation
+    /// and value as percentages ranging from 0.0 to 100.0, and the alpha ranging from 0.0 to 1.0.
+    /// Grayscale colors have a hue and saturation of 0.0.
+    fn to_hsv(&self) -> (f32, f32, f32, f32);
+
+    /// Creates a `Solid` from a color with premultiplied alpha.
+    ///
+    /// # Parameters
+    /// - `color`: The color with its red, green and blue channels already multiplied by its alpha.
+    ///
+    /// # Returns
+    /// A new `Solid` storing the straight alpha color, with no associated brush. A fully
+    /// transparent color becomes transparent black.
+    fn from_premultiplied(color: D2D1_COLOR_F) -> Solid;
+
+    /// Converts the color into premultiplied alpha.
+    ///
+    /// This is meant for writing raw pixel data. Brushes take straight alpha colors, since
+    /// Direct2D premultiplies them itself.
+    ///
+    /// # Returns
+    /// The color with its red, green and blue channels multiplied by its alpha.
+    fn to_premultiplied(&self) -> D2D1_COLOR_F;
+
+    /// Checks that the color can safely be used to create a brush.
+    ///
+    /// Every channel and the opacity must be finite and within 0.0 to 1.0. `NaN` values fail the
+    /// check, since they are not within any range.
+    ///
+    /// # Returns
+    /// `Ok(())` if the color is valid, or an `ErrorKind::InvalidData` error otherwise.
+    fn validate(&self) -> Result<()>;
+
+    /// Returns a copy of the color with its alpha channel replaced.
+    ///
+    /// The red, green and blue channels, the opacity and the transform are left unchanged.
+    ///
+    /// # Parameters
+    /// - `a`: The new alpha, clamped to 0.0 to 1.0.
+    ///
+    /// # Returns
+    /// A new `Solid` with no associated brush.
+    fn with_alpha(&self, a: f32) -> Solid;
+
+    /// Returns a copy of the color with `delta` added to a single channel.
+    ///
+    /// This is a cheaper alternative to an HSL round-trip for quick adjustments, such as boosting
+    /// one channel for a highlight effect. The opacity and transform are left unchanged.
+    ///
+    /// # Parameters
+    /// - `channel`: The channel to adjust.
+    /// - `delta`: The amount to add to the channel. The result is clamped to 0.0 to 1.0.
+    ///
+    /// # Returns
+    /// A new `Solid` with no associated brush.
+    fn tint(&self, channel: Channel, delta: f32) -> Solid;
+
+    /// Picks black or white, whichever is more readable as text on this color.
+    ///
+    /// Colors with a relative luminance above 0.179 get black text and darker colors get white
+    /// text. At that luminance both choices have the same contrast ratio.
+    ///
+    /// # Returns
+    /// An opaque black or white `Solid` with no associated brush.
+    fn readable_foreground(&self) -> Solid;
+
+    /// Promotes the color into a linear gradient that shows the same color everywhere.
+    ///
+    /// The gradient has two stops at positions 0.0 and 1.0, both with this color, so it can be
+    /// interpolated with other gradients, for example to animate from a solid color into a
+    /// gradient with `ColorImpl::lerp`. The opacity and transform are carried over.
+    ///
+    /// # Parameters
+    /// - `direction`: The normalized start and end points of the gradient.
+    ///
+    /// # Returns
+    /// A new `Gradient` with no associated brush.
+    fn to_gradient(&self, direction: GradientCoordinates) -> Gradient;
+}
+
+/// A single channel of a `D2D1_COLOR_F`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Channel {
+    /// The red channel.
+    R,
+    /// The green channel.
+    G,
+    /// The blue channel.
+    B,
+    /// The alpha channel.
+    A,
+}
+
+/// Computes the WCAG contrast ratio between two solid colors.
+///
+/// The ratio is symmetric, so the order of the colors does not matter.
+///
+/// # Parameters
+/// - `a`: The first color.
+/// - `b`: The second color.
+///
+/// # Returns
+/// The contrast ratio, ranging from 1.0 (identical luminance) to 21.0 (black on white).
+pub fn contrast_ratio(a: &Solid, b: &Solid) -> f32 {
+    let (la, lb) = (a.relative_luminance(), b.relative_luminance());
+    let (lighter, darker) = if la > lb { (la, lb) } else { (lb, la) };
+
+    (lighter + 0.05) / (darker + 0.05)
+}
+
 /// Represents a solid color with a specific opacity.
 ///
 /// The `Solid` struct defines a color with an associated opacity.
@@ -9,19 +237,485 @@ use windows::Win32::Graphics::Direct2D::ID2D1SolidColorBrush;
 ///
 /// # Fields
 /// - `color`: A `D2D1_COLOR_F` struct that represents the color in RGBA format, with values for red, green, blue, and alpha (opacity) in the range [0.0, 1.0].
+/// - `opacity`: The opacity applied to the brush when it is created, ranging from 0.0 to 1.0. It
+///   multiplies with the color's own alpha and the opacity of the brush properties, and defaults
+///   to 1.0.
+/// - `transform`: An optional transform applied to the brush whenever it is created.
 /// - `brush`: An optional `ID2D1SolidColorBrush` that represents the color as a brush, used for rendering the solid color. It may be `None` if not initialized.
 ///
 /// # Example
 /// ```rust
 /// use windows::Win32::Graphics::Direct2D::Common::D2D1_COLOR_F;
-/// let solid_color = Solid {
-///     color: D2D1_COLOR_F { r: 0.5, g: 0.0, b: 0.0, a: 1.0 },
-///     brush: None,  // or Some(brush_instance) if a brush is initialized
-/// };
+/// # use win_color::Solid;
+/// let solid_color = Solid::from(D2D1_COLOR_F { r: 0.5, g: 0.0, b: 0.0, a: 1.0 });
 /// ```
 /// This creates a red color with full opacity and no associated brush.
-#[derive(Debug, Clone, PartialEq)]
+///
+/// Two `Solid`s are equal when their `color`, `opacity` and `transform` are equal and both or
+/// neither are the `none` color. The cached `brush` is ignored, so a color compares equal to a
+/// copy whose brush has not been created yet.
+#[derive(Debug, Clone)]
 pub struct Solid {
     pub color: D2D1_COLOR_F,
+    /// The authored opacity of the brush. Colors parsed with an alpha channel, such as an 8-digit
+    /// hex or `rgba()` color, carry that alpha here and keep an opaque `color`.
+    /// `ColorImpl::set_opacity` overrides the brush opacity until the brush is recreated, after
+    /// which this opacity applies again.
+    pub opacity: f32,
+    /// The authored transform of the brush. When set, it replaces the transform of the brush
+    /// properties each time the brush is created, so it survives brush recreation.
+    pub transform: Option<Matrix3x2>,
     pub brush: Option<ID2D1SolidColorBrush>,
+    /// A hash of the inputs the `brush` was created from, used to reuse the brush when they
+    /// haven't changed.
+    pub(crate) brush_key: Option<u64>,
+    /// The render target the `brush` was created on. It is kept alive with the brush, so the
+    /// brush is only reused on that same target.
+    pub(crate) brush_target: Option<ID2D1RenderTarget>,
+    /// Whether this is the `none` color created by `ColorImpl::none`, which is never drawn.
+    pub(crate) none: bool,
+}
+
+impl From<D2D1_COLOR_F> for Solid {
+    fn from(color: D2D1_COLOR_F) -> Self {
+        Self {
+            color,
+            opacity: 1.0,
+            transform: None,
+            brush: None,
+            brush_key: None,
+            brush_target: None,
+            none: false,
+        }
+    }
+}
+
+impl PartialEq for Solid {
+    fn eq(&self, other: &Self) -> bool {
+        self.color == other.color
+            && self.opacity == other.opacity
+            && self.transform == other.transform
+            && self.none == other.none
+    }
+}
+
+impl SolidImpl for Solid {
+    fn from_colorref(colorref: u32) -> Solid {
+        Solid::from_rgba8(
+            (colorref & 0xFF) as u8,
+            ((colorref >> 8) & 0xFF) as u8,
+            ((colorref >> 16) & 0xFF) as u8,
+            u8::MAX,
+        )
+    }
+
+    fn to_colorref(&self) -> u32 {
+        let (r, g, b, _) = self.to_rgba8();
+
+        r as u32 | ((g as u32) << 8) | ((b as u32) << 16)
+    }
+
+    fn from_rgba8(r: u8, g: u8, b: u8, a: u8) -> Solid {
+        Solid::from(D2D1_COLOR_F {
+            r: r as f32 / 255.0,
+            g: g as f32 / 255.0,
+            b: b as f32 / 255.0,
+            a: a as f32 / 255.0,
+        })
+    }
+
+    fn from_hex(hex: &str) -> Result<Solid> {
+        parse_hex_color(hex).map(Solid::from)
+    }
+
+    fn effective_color(&self) -> D2D1_COLOR_F {
+        with_alpha(self.color, self.color.a * self.opacity)
+    }
+
+    fn to_rgba8(&self) -> (u8, u8, u8, u8) {
+        (
+            channel_to_u8(self.color.r),
+            channel_to_u8(self.color.g),
+            channel_to_u8(self.color.b),
+            channel_to_u8(self.color.a),
+        )
+    }
+
+    fn from_bgra8(b: u8, g: u8, r: u8, a: u8) -> Solid {
+        Solid::from_rgba8(r, g, b, a)
+    }
+
+    fn to_bgra8(&self) -> (u8, u8, u8, u8) {
+        let (r, g, b, a) = self.to_rgba8();
+        (b, g, r, a)
+    }
+
+    fn relative_luminance(&self) -> f32 {
+        0.2126 * srgb_to_linear(self.color.r)
+            + 0.7152 * srgb_to_linear(self.color.g)
+            + 0.0722 * srgb_to_linear(self.color.b)
+    }
+
+    fn with_hsv(h: f32, s: f32, v: f32, a: f32) -> Solid {
+        Solid::from(hsv_to_d2d1(Hsva { h, s, v, a }))
+    }
+
+    fn to_hsv(&self) -> (f32, f32, f32, f32) {
+        let Hsva { h, s, v, a } = d2d1_to_hsv(self.color);
+        (h, s, v, a)
+    }
+
+    fn from_premultiplied(color: D2D1_COLOR_F) -> Solid {
+        Solid::from(unpremultiply(color))
+    }
+
+    fn to_premultiplied(&self) -> D2D1_COLOR_F {
+        premultiply(self.color)
+    }
+
+    fn with_alpha(&self, a: f32) -> Solid {
+        Solid {
+            opacity: self.opacity,
+            transform: self.transform,
+            ..Solid::from(with_alpha(self.color, a))
+        }
+    }
+
+    fn tint(&self, channel: Channel, delta: f32) -> Solid {
+        let mut color = self.color;
+        let value = match channel {
+            Channel::R => &mut color.r,
+            Channel::G => &mut color.g,
+            Channel::B => &mut color.b,
+            Channel::A => &mut color.a,
+        };
+        *value = (*value + delta).clamp(0.0, 1.0);
+
+        Solid {
+            opacity: self.opacity,
+            transform: self.transform,
+            ..Solid::from(color)
+        }
+    }
+
+    fn readable_foreground(&self) -> Solid {
+        let channel = if self.relative_luminance() > 0.179 {
+            0.0
+        } else {
+            1.0
+        };
+
+        Solid::from(D2D1_COLOR_F {
+            r: channel,
+            g: channel,
+            b: channel,
+            a: 1.0,
+        })
+    }
+
+    fn to_gradient(&self, direction: GradientCoordinates) -> Gradient {
+        let stop = |position: f32| D2D1_GRADIENT_STOP {
+            position,
+            color: self.color,
+        };
+
+        Gradient {
+            opacity: self.opacity,
+            transform: self.transform,
+            ..Gradient::new(direction, vec![stop(0.0), stop(1.0)], GradientShape::Linear)
+        }
+    }
+
+    fn validate(&self) -> Result<()> {
+        validate_color(&self.color)?;
+
+        if !(0.0..=1.0).contains(&self.opacity) {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("The opacity {} is not within 0.0 to 1.0", self.opacity),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn color(r: f32, g: f32, b: f32, a: f32) -> D2D1_COLOR_F {
+        D2D1_COLOR_F { r, g, b, a }
+    }
+
+    #[test]
+    fn colorref_round_trips_within_rounding() {
+        let solid = Solid::from(color(0.2, 0.5, 0.8, 1.0));
+        let colorref = solid.to_colorref();
+        let round_trip = Solid::from_colorref(colorref);
+
+        assert_eq!(colorref, 0x00CC8033);
+        for (a, b) in [
+            (solid.color.r, round_trip.color.r),
+            (solid.color.g, round_trip.color.g),
+            (solid.color.b, round_trip.color.b),
+        ] {
+            assert!((a - b).abs() <= 1.0 / 255.0);
+        }
+    }
+
+    #[test]
+    fn colorref_drops_alpha() {
+        assert_eq!(
+            Solid::from(color(1.0, 0.0, 0.0, 0.5)).to_colorref(),
+            0x000000FF
+        );
+        assert_eq!(
+            Solid::from_colorref(0x00FF0000).color,
+            color(0.0, 0.0, 1.0, 1.0)
+        );
+    }
+
+    #[test]
+    fn rgba8_converts_exact_values() {
+        assert_eq!(
+            Solid::from_rgba8(255, 0, 0, 255).color,
+            color(1.0, 0.0, 0.0, 1.0)
+        );
+        assert_eq!(
+            Solid::from_rgba8(255, 255, 255, 255).color,
+            color(1.0, 1.0, 1.0, 1.0)
+        );
+        assert_eq!(
+            Solid::from_rgba8(128, 128, 128, 255).to_rgba8(),
+            (128, 128, 128, 255)
+        );
+
+        assert_eq!(
+            Solid::from(color(1.0, 0.0, 0.0, 1.0)).to_rgba8(),
+            (255, 0, 0, 255)
+        );
+        assert_eq!(
+            Solid::from(color(1.0, 1.0, 1.0, 1.0)).to_rgba8(),
+            (255, 255, 255, 255)
+        );
+        assert_eq!(
+            Solid::from(color(0.5, 0.5, 0.5, 0.5)).to_rgba8(),
+            (128, 128, 128, 128)
+        );
+    }
+
+    #[test]
+    fn contrast_ratio_of_black_on_white_is_21() {
+        let black = Solid::from(color(0.0, 0.0, 0.0, 1.0));
+        let white = Solid::from(color(1.0, 1.0, 1.0, 1.0));
+
+        assert_eq!(black.relative_luminance(), 0.0);
+        assert_eq!(white.relative_luminance(), 1.0);
+        assert!((contrast_ratio(&black, &white) - 21.0).abs() < 1e-4);
+        assert!((contrast_ratio(&white, &black) - 21.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn contrast_ratio_of_identical_colors_is_1() {
+        let gray = Solid::from(color(0.5, 0.5, 0.5, 1.0));
+
+        assert!((contrast_ratio(&gray, &gray) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn hsv_converts_primary_colors_and_gray() {
+        for (rgb, hsv) in [
+            (color(1.0, 0.0, 0.0, 1.0), (0.0, 100.0, 100.0, 1.0)),
+            (color(0.0, 1.0, 0.0, 1.0), (120.0, 100.0, 100.0, 1.0)),
+            (color(0.0, 0.0, 1.0, 1.0), (240.0, 100.0, 100.0, 1.0)),
+            (color(0.5, 0.5, 0.5, 0.5), (0.0, 0.0, 50.0, 0.5)),
+        ] {
+            assert_eq!(Solid::from(rgb).to_hsv(), hsv);
+            assert_eq!(Solid::with_hsv(hsv.0, hsv.1, hsv.2, hsv.3).color, rgb);
+        }
+    }
+
+    #[test]
+    fn hsv_wraps_hue() {
+        let red = color(1.0, 0.0, 0.0, 1.0);
+
+        assert_eq!(Solid::with_hsv(360.0, 100.0, 100.0, 1.0).color, red);
+        assert_eq!(
+            Solid::with_hsv(-240.0, 100.0, 100.0, 1.0).color,
+            color(0.0, 1.0, 0.0, 1.0)
+        );
+        assert_eq!(Solid::with_hsv(720.0, 100.0, 100.0, 1.0).color, red);
+    }
+
+    #[test]
+    fn premultiplies_half_transparent_colors() {
+        let solid = Solid::from(color(1.0, 0.5, 0.0, 0.5));
+
+        assert_eq!(solid.to_premultiplied(), color(0.5, 0.25, 0.0, 0.5));
+    }
+
+    #[test]
+    fn unpremultiplies_half_transparent_colors() {
+        let solid = Solid::from_premultiplied(color(0.5, 0.25, 0.0, 0.5));
+
+        assert_eq!(solid.color, color(1.0, 0.5, 0.0, 0.5));
+        assert_eq!(
+            Solid::from_premultiplied(solid.to_premultiplied()).color,
+            solid.color
+        );
+    }
+
+    #[test]
+    fn unpremultiplying_transparent_colors_gives_transparent_black() {
+        let solid = Solid::from_premultiplied(color(0.5, 0.5, 0.5, 0.0));
+
+        assert_eq!(solid.color, color(0.0, 0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn validate_rejects_non_finite_channels() {
+        for (solid, channel) in [
+            (Solid::from(color(1.0, 0.0, 0.0, f32::NAN)), "alpha"),
+            (Solid::from(color(f32::INFINITY, 0.0, 0.0, 1.0)), "red"),
+            (Solid::from(color(0.0, 1.5, 0.0, 1.0)), "green"),
+        ] {
+            let error = solid.validate().unwrap_err();
+
+            assert_eq!(error.kind(), ErrorKind::InvalidData);
+            assert!(error.to_string().contains(channel), "{}", error);
+        }
+    }
+
+    #[test]
+    fn validate_checks_opacity() {
+        let solid = Solid {
+            opacity: f32::NAN,
+            ..Solid::from(color(1.0, 0.0, 0.0, 1.0))
+        };
+
+        assert_eq!(solid.validate().unwrap_err().kind(), ErrorKind::InvalidData);
+        assert!(Solid::from(color(1.0, 0.0, 0.0, 1.0)).validate().is_ok());
+    }
+
+    #[test]
+    fn from_hex_accepts_every_length() {
+        for (hex, rgba8) in [
+            ("#f80", (0xff, 0x88, 0x00, 0xff)),
+            ("#f808", (0xff, 0x88, 0x00, 0x88)),
+            ("#ff5733", (0xff, 0x57, 0x33, 0xff)),
+            ("#FF573380", (0xff, 0x57, 0x33, 0x80)),
+        ] {
+            assert_eq!(Solid::from_hex(hex).unwrap().to_rgba8(), rgba8, "{}", hex);
+        }
+    }
+
+    #[test]
+    fn from_hex_rejects_malformed_input() {
+        for hex in [
+            "", "#", "ff5733", "#ff573", "#ff5733f", "#gg5733", "#ff5733 ",
+        ] {
+            let error = Solid::from_hex(hex).unwrap_err();
+
+            assert_eq!(error.kind(), ErrorKind::InvalidHex, "{}", hex);
+        }
+    }
+
+    #[test]
+    fn with_alpha_leaves_rgb_bit_identical() {
+        let solid = Solid {
+            opacity: 0.5,
+            ..Solid::from(color(0.1, 0.7, 0.3, 1.0))
+        };
+
+        for (a, expected) in [(0.25, 0.25), (-1.0, 0.0), (2.0, 1.0)] {
+            let adjusted = solid.with_alpha(a);
+
+            assert_eq!(adjusted.color.r.to_bits(), solid.color.r.to_bits());
+            assert_eq!(adjusted.color.g.to_bits(), solid.color.g.to_bits());
+            assert_eq!(adjusted.color.b.to_bits(), solid.color.b.to_bits());
+            assert_eq!(adjusted.color.a, expected);
+            assert_eq!(adjusted.opacity, 0.5);
+        }
+    }
+
+    #[test]
+    fn tint_adjusts_a_single_channel() {
+        let solid = Solid::from(color(0.25, 0.25, 0.25, 0.5));
+
+        for (channel, expected) in [
+            (Channel::R, color(0.5, 0.25, 0.25, 0.5)),
+            (Channel::G, color(0.25, 0.5, 0.25, 0.5)),
+            (Channel::B, color(0.25, 0.25, 0.5, 0.5)),
+            (Channel::A, color(0.25, 0.25, 0.25, 0.75)),
+        ] {
+            assert_eq!(solid.tint(channel, 0.25).color, expected, "{:?}", channel);
+        }
+    }
+
+    #[test]
+    fn tint_clamps_to_the_channel_range() {
+        let solid = Solid::from(color(0.9, 0.1, 0.5, 1.0));
+
+        assert_eq!(solid.tint(Channel::R, 0.5).color.r, 1.0);
+        assert_eq!(solid.tint(Channel::A, 0.5).color.a, 1.0);
+        assert_eq!(solid.tint(Channel::G, -0.5).color.g, 0.0);
+    }
+
+    #[test]
+    fn readable_foreground_contrasts_with_the_background() {
+        let white = color(1.0, 1.0, 1.0, 1.0);
+        let black = color(0.0, 0.0, 0.0, 1.0);
+
+        for (background, expected) in [
+            (color(0.1, 0.1, 0.2, 1.0), white),
+            (color(0.0, 0.0, 1.0, 1.0), white),
+            (color(0.9, 0.9, 0.8, 1.0), black),
+            (color(1.0, 1.0, 0.0, 1.0), black),
+        ] {
+            let foreground = Solid::from(background).readable_foreground();
+
+            assert_eq!(foreground.color, expected, "{:?}", background);
+        }
+    }
+
+    #[test]
+    fn to_gradient_repeats_the_color() {
+        let solid = Solid {
+            opacity: 0.5,
+            ..Solid::from(color(0.2, 0.4, 0.6, 0.8))
+        };
+        let direction = GradientCoordinates {
+            start: [0.0, 0.0],
+            end: [1.0, 1.0],
+        };
+        let gradient = solid.to_gradient(direction.clone());
+        let stops: Vec<_> = gradient
+            .gradient_stops
+            .iter()
+            .map(|stop| (stop.position, stop.color))
+            .collect();
+
+        assert_eq!(stops, [(0.0, solid.color), (1.0, solid.color)]);
+        assert_eq!(gradient.direction, direction);
+        assert_eq!(gradient.opacity, 0.5);
+        assert!(gradient.brush.is_none());
+    }
+
+    #[test]
+    fn bgra8_of_blue_bytes_is_blue() {
+        let blue = Solid::from_bgra8(0xFF, 0x00, 0x00, 0xFF);
+
+        assert_eq!(blue.color, color(0.0, 0.0, 1.0, 1.0));
+        assert_eq!(blue.to_bgra8(), (0xFF, 0x00, 0x00, 0xFF));
+        assert_eq!(blue.to_rgba8(), (0x00, 0x00, 0xFF, 0xFF));
+    }
+
+    #[test]
+    fn bgra8_round_trips() {
+        let solid = Solid::from_bgra8(0x33, 0x66, 0x99, 0x80);
+
+        assert_eq!(solid.to_bgra8(), (0x33, 0x66, 0x99, 0x80));
+        assert_eq!(solid.to_rgba8(), (0x99, 0x66, 0x33, 0x80));
+    }
 }
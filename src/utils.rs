@@ -0,0 +1,649 @@
+//! This module provides color space conversions and adjustment helpers operating on `D2D1_COLOR_F`.
+
+use windows::Win32::Graphics::Direct2D::Common::D2D1_COLOR_F;
+
+use crate::error::Error;
+use crate::error::ErrorKind;
+use crate::error::Result;
+
+/// Represents a color in the HSLA color space.
+///
+/// # Fields
+/// - `h`: The hue in degrees, ranging from 0.0 to 360.0.
+/// - `s`: The saturation as a percentage, ranging from 0.0 to 100.0.
+/// - `l`: The lightness as a percentage, ranging from 0.0 to 100.0.
+/// - `a`: The alpha (opacity), ranging from 0.0 to 1.0.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Hsla {
+    pub h: f32,
+    pub s: f32,
+    pub l: f32,
+    pub a: f32,
+}
+
+/// Converts a `D2D1_COLOR_F` into its `Hsla` representation.
+///
+/// # Parameters
+/// - `color`: The color to convert, with channels in the range 0.0 to 1.0.
+///
+/// # Returns
+/// The equivalent `Hsla` color.
+pub(crate) fn d2d1_to_hsla(color: D2D1_COLOR_F) -> Hsla {
+    let max = color.r.max(color.g).max(color.b);
+    let min = color.r.min(color.g).min(color.b);
+    let delta = max - min;
+
+    let l = (max + min) / 2.0;
+
+    // Grayscale colors have neither hue nor saturation.
+    if delta == 0.0 {
+        return Hsla {
+            h: 0.0,
+            s: 0.0,
+            l: l * 100.0,
+            a: color.a,
+        };
+    }
+
+    let s = delta / (1.0 - (2.0 * l - 1.0).abs());
+
+    Hsla {
+        h: hue(color, max, delta),
+        s: s * 100.0,
+        l: l * 100.0,
+        a: color.a,
+    }
+}
+
+/// Converts an `Hsla` color back into a `D2D1_COLOR_F`.
+///
+/// # Parameters
+/// - `hsla`: The color to convert. The hue is wrapped into 0.0 to 360.0 and the saturation and
+///   lightness are clamped to 0.0 to 100.0.
+///
+/// # Returns
+/// The equivalent `D2D1_COLOR_F` with channels in the range 0.0 to 1.0.
+pub(crate) fn hsla_to_d2d1(hsla: Hsla) -> D2D1_COLOR_F {
+    let h = hsla.h.rem_euclid(360.0);
+    let s = hsla.s.clamp(0.0, 100.0) / 100.0;
+    let l = hsla.l.clamp(0.0, 100.0) / 100.0;
+
+    let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    let (r, g, b) = hue_to_rgb(h, c);
+    let m = l - c / 2.0;
+
+    D2D1_COLOR_F {
+        r: r + m,
+        g: g + m,
+        b: b + m,
+        a: hsla.a,
+    }
+}
+
+/// Represents a color in the HSVA color space.
+///
+/// # Fields
+/// - `h`: The hue in degrees, ranging from 0.0 to 360.0.
+/// - `s`: The saturation as a percentage, ranging from 0.0 to 100.0.
+/// - `v`: The value (brightness) as a percentage, ranging from 0.0 to 100.0.
+/// - `a`: The alpha (opacity), ranging from 0.0 to 1.0.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Hsva {
+    pub h: f32,
+    pub s: f32,
+    pub v: f32,
+    pub a: f32,
+}
+
+/// Converts a `D2D1_COLOR_F` into its `Hsva` representation.
+///
+/// # Parameters
+/// - `color`: The color to convert, with channels in the range 0.0 to 1.0.
+///
+/// # Returns
+/// The equivalent `Hsva` color. Grayscale colors have a hue and saturation of 0.0.
+pub(crate) fn d2d1_to_hsv(color: D2D1_COLOR_F) -> Hsva {
+    let max = color.r.max(color.g).max(color.b);
+    let min = color.r.min(color.g).min(color.b);
+    let delta = max - min;
+
+    // Grayscale colors have neither hue nor saturation.
+    if delta == 0.0 {
+        return Hsva {
+            h: 0.0,
+            s: 0.0,
+            v: max * 100.0,
+            a: color.a,
+        };
+    }
+
+    Hsva {
+        h: hue(color, max, delta),
+        s: delta / max * 100.0,
+        v: max * 100.0,
+        a: color.a,
+    }
+}
+
+/// Converts an `Hsva` color back into a `D2D1_COLOR_F`.
+///
+/// # Parameters
+/// - `hsva`: The color to convert. The hue is wrapped into 0.0 to 360.0 and the saturation and
+///   value are clamped to 0.0 to 100.0.
+///
+/// # Returns
+/// The equivalent `D2D1_COLOR_F` with channels in the range 0.0 to 1.0.
+pub(crate) fn hsv_to_d2d1(hsva: Hsva) -> D2D1_COLOR_F {
+    let h = hsva.h.rem_euclid(360.0);
+    let s = hsva.s.clamp(0.0, 100.0) / 100.0;
+    let v = hsva.v.clamp(0.0, 100.0) / 100.0;
+
+    let c = v * s;
+    let (r, g, b) = hue_to_rgb(h, c);
+    let m = v - c;
+
+    D2D1_COLOR_F {
+        r: r + m,
+        g: g + m,
+        b: b + m,
+        a: hsva.a,
+    }
+}
+
+/// Computes the hue in degrees of a non-grayscale color, given its largest channel and the
+/// difference between its largest and smallest channels.
+fn hue(color: D2D1_COLOR_F, max: f32, delta: f32) -> f32 {
+    if max == color.r {
+        60.0 * ((color.g - color.b) / delta).rem_euclid(6.0)
+    } else if max == color.g {
+        60.0 * ((color.b - color.r) / delta + 2.0)
+    } else {
+        60.0 * ((color.r - color.g) / delta + 4.0)
+    }
+}
+
+/// Computes the red, green and blue channels for a hue in degrees and a chroma, before the
+/// lightness or value offset is added.
+fn hue_to_rgb(h: f32, c: f32) -> (f32, f32, f32) {
+    let x = c * (1.0 - ((h / 60.0).rem_euclid(2.0) - 1.0).abs());
+
+    match h {
+        h if h < 60.0 => (c, x, 0.0),
+        h if h < 120.0 => (x, c, 0.0),
+        h if h < 180.0 => (0.0, c, x),
+        h if h < 240.0 => (0.0, x, c),
+        h if h < 300.0 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    }
+}
+
+/// Darkens a color by reducing its lightness by the given percentage.
+///
+/// The lightness is computed from the sRGB-encoded channels as they are stored. See
+/// `darken_linear` for a variant working in linear light.
+///
+/// # Parameters
+/// - `color`: The color to darken.
+/// - `percentage`: How much to reduce the lightness by, relative to its current value.
+///
+/// # Returns
+/// The darkened color.
+pub(crate) fn darken(color: D2D1_COLOR_F, percentage: f32) -> D2D1_COLOR_F {
+    let mut hsla = d2d1_to_hsla(color);
+    hsla.l -= hsla.l * percentage / 100.0;
+    hsla_to_d2d1(hsla)
+}
+
+/// Lightens a color by increasing its lightness by the given percentage.
+///
+/// The lightness is computed from the sRGB-encoded channels as they are stored. See
+/// `lighten_linear` for a variant working in linear light.
+///
+/// # Parameters
+/// - `color`: The color to lighten.
+/// - `percentage`: How much to increase the lightness by, relative to its current value.
+///
+/// # Returns
+/// The lightened color.
+pub(crate) fn lighten(color: D2D1_COLOR_F, percentage: f32) -> D2D1_COLOR_F {
+    let mut hsla = d2d1_to_hsla(color);
+    hsla.l += hsla.l * percentage / 100.0;
+    hsla_to_d2d1(hsla)
+}
+
+/// Darkens a color by reducing its lightness in linear light by the given percentage.
+///
+/// The channels are linearized before the lightness is adjusted and re-encoded as sRGB
+/// afterwards. Since equal steps in linear light are not perceptually even, this darkens
+/// mid-tones less visibly than `darken`, which matches how light physically dims.
+///
+/// # Parameters
+/// - `color`: The sRGB color to darken.
+/// - `percentage`: How much to reduce the linear lightness by, relative to its current value.
+///
+/// # Returns
+/// The darkened sRGB color.
+pub(crate) fn darken_linear(color: D2D1_COLOR_F, percentage: f32) -> D2D1_COLOR_F {
+    map_linear(color, |color| darken(color, percentage))
+}
+
+/// Lightens a color by increasing its lightness in linear light by the given percentage.
+///
+/// The channels are linearized before the lightness is adjusted and re-encoded as sRGB
+/// afterwards. Since equal steps in linear light are not perceptually even, this lightens
+/// mid-tones less visibly than `lighten`, which matches how light physically adds up.
+///
+/// # Parameters
+/// - `color`: The sRGB color to lighten.
+/// - `percentage`: How much to increase the linear lightness by, relative to its current value.
+///
+/// # Returns
+/// The lightened sRGB color.
+pub(crate) fn lighten_linear(color: D2D1_COLOR_F, percentage: f32) -> D2D1_COLOR_F {
+    map_linear(color, |color| lighten(color, percentage))
+}
+
+/// Applies `f` to a color in linear light, converting its red, green and blue channels from sRGB
+/// and back. The alpha channel is passed through unchanged.
+fn map_linear(color: D2D1_COLOR_F, f: impl Fn(D2D1_COLOR_F) -> D2D1_COLOR_F) -> D2D1_COLOR_F {
+    let linear = f(D2D1_COLOR_F {
+        r: srgb_to_linear(color.r),
+        g: srgb_to_linear(color.g),
+        b: srgb_to_linear(color.b),
+        a: color.a,
+    });
+
+    D2D1_COLOR_F {
+        r: linear_to_srgb(linear.r),
+        g: linear_to_srgb(linear.g),
+        b: linear_to_srgb(linear.b),
+        a: linear.a,
+    }
+}
+
+/// Rotates the hue of a color by the given number of degrees.
+///
+/// # Parameters
+/// - `color`: The color to rotate.
+/// - `degrees`: The rotation in degrees. Negative values rotate backwards, and the resulting hue
+///   is wrapped into 0.0 to 360.0.
+///
+/// # Returns
+/// The rotated color. Fully gray colors have no hue and are left unchanged.
+pub(crate) fn rotate_hue(color: D2D1_COLOR_F, degrees: f32) -> D2D1_COLOR_F {
+    let mut hsla = d2d1_to_hsla(color);
+    hsla.h = (hsla.h + degrees).rem_euclid(360.0);
+    hsla_to_d2d1(hsla)
+}
+
+/// Saturates a color by increasing its saturation by the given percentage.
+///
+/// The saturation is adjusted relative to its current value and clamped to 0.0 to 100.0, so
+/// fully gray colors are left unchanged.
+///
+/// # Parameters
+/// - `color`: The color to saturate.
+/// - `percentage`: How much to increase the saturation by, relative to its current value.
+///
+/// # Returns
+/// The saturated color.
+pub fn saturate(color: D2D1_COLOR_F, percentage: f32) -> D2D1_COLOR_F {
+    let mut hsla = d2d1_to_hsla(color);
+    hsla.s = (hsla.s + hsla.s * percentage / 100.0).clamp(0.0, 100.0);
+    hsla_to_d2d1(hsla)
+}
+
+/// Desaturates a color by reducing its saturation by the given percentage.
+///
+/// The saturation is adjusted relative to its current value and clamped to 0.0 to 100.0, so
+/// fully gray colors are left unchanged.
+///
+/// # Parameters
+/// - `color`: The color to desaturate.
+/// - `percentage`: How much to reduce the saturation by, relative to its current value.
+///
+/// # Returns
+/// The desaturated color.
+pub fn desaturate(color: D2D1_COLOR_F, percentage: f32) -> D2D1_COLOR_F {
+    let mut hsla = d2d1_to_hsla(color);
+    hsla.s = (hsla.s - hsla.s * percentage / 100.0).clamp(0.0, 100.0);
+    hsla_to_d2d1(hsla)
+}
+
+/// Replaces the alpha channel of a color.
+///
+/// Unlike adjustments going through HSLA, the red, green and blue channels are left bit-identical.
+///
+/// # Parameters
+/// - `color`: The color to adjust.
+/// - `a`: The new alpha, clamped to 0.0 to 1.0.
+///
+/// # Returns
+/// The color with its alpha replaced.
+pub fn with_alpha(color: D2D1_COLOR_F, a: f32) -> D2D1_COLOR_F {
+    D2D1_COLOR_F {
+        a: a.clamp(0.0, 1.0),
+        ..color
+    }
+}
+
+/// Formats a color as a `#RRGGBB` hex string, or `#RRGGBBAA` when it is not fully opaque.
+///
+/// # Parameters
+/// - `color`: The color to format, with channels in the range 0.0 to 1.0.
+///
+/// # Returns
+/// The hex string representation of the color.
+pub(crate) fn to_hex(color: &D2D1_COLOR_F) -> String {
+    let (r, g, b, a) = (
+        channel_to_u8(color.r),
+        channel_to_u8(color.g),
+        channel_to_u8(color.b),
+        channel_to_u8(color.a),
+    );
+
+    if a == u8::MAX {
+        format!("#{:02x}{:02x}{:02x}", r, g, b)
+    } else {
+        format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, a)
+    }
+}
+
+/// Formats a color as a lowercase `#rrggbbaa` hex string, always including the alpha.
+///
+/// This is the canonical color form used by `ColorMappingImpl::canonicalize`.
+///
+/// # Parameters
+/// - `color`: The color to format, with channels in the range 0.0 to 1.0.
+///
+/// # Returns
+/// The hex string representation of the color.
+pub(crate) fn to_hex_rgba(color: &D2D1_COLOR_F) -> String {
+    format!(
+        "#{:02x}{:02x}{:02x}{:02x}",
+        channel_to_u8(color.r),
+        channel_to_u8(color.g),
+        channel_to_u8(color.b),
+        channel_to_u8(color.a)
+    )
+}
+
+/// Converts a normalized color channel into an 8-bit value.
+///
+/// The channel is clamped to 0.0 to 1.0 and rounded to the nearest value, so 0.5 becomes 128.
+///
+/// # Parameters
+/// - `channel`: The channel value, ranging from 0.0 to 1.0.
+///
+/// # Returns
+/// The channel value ranging from 0 to 255.
+pub(crate) fn channel_to_u8(channel: f32) -> u8 {
+    (channel.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+/// Linearly interpolates between two colors channel-wise.
+///
+/// # Parameters
+/// - `from`: The color at `t = 0.0`.
+/// - `to`: The color at `t = 1.0`.
+/// - `t`: The interpolation factor.
+///
+/// # Returns
+/// The interpolated color.
+pub(crate) fn lerp_color(from: &D2D1_COLOR_F, to: &D2D1_COLOR_F, t: f32) -> D2D1_COLOR_F {
+    D2D1_COLOR_F {
+        r: lerp(from.r, to.r, t),
+        g: lerp(from.g, to.g, t),
+        b: lerp(from.b, to.b, t),
+        a: lerp(from.a, to.a, t),
+    }
+}
+
+/// Linearly interpolates between two values.
+pub(crate) fn lerp(from: f32, to: f32, t: f32) -> f32 {
+    from + (to - from) * t
+}
+
+/// Converts an sRGB-encoded channel into linear light.
+///
+/// # Parameters
+/// - `channel`: The sRGB channel value, ranging from 0.0 to 1.0.
+///
+/// # Returns
+/// The linear channel value, ranging from 0.0 to 1.0.
+pub(crate) fn srgb_to_linear(channel: f32) -> f32 {
+    if channel <= 0.04045 {
+        channel / 12.92
+    } else {
+        ((channel + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Converts an OKLab color into a `D2D1_COLOR_F`.
+///
+/// The color is converted through linear sRGB, and each linear channel is clamped to 0.0 to 1.0
+/// before sRGB encoding, so colors outside the sRGB gamut are clipped to its nearest edge per
+/// channel rather than mapped perceptually.
+///
+/// # Parameters
+/// - `l`: The perceived lightness, ranging from 0.0 to 1.0.
+/// - `a`: The green-red axis, typically ranging from -0.4 to 0.4.
+/// - `b`: The blue-yellow axis, typically ranging from -0.4 to 0.4.
+/// - `alpha`: The alpha (opacity), ranging from 0.0 to 1.0.
+///
+/// # Returns
+/// The equivalent `D2D1_COLOR_F` with channels in the range 0.0 to 1.0.
+pub(crate) fn oklab_to_d2d1(l: f32, a: f32, b: f32, alpha: f32) -> D2D1_COLOR_F {
+    let l_ = l + 0.396_337_78 * a + 0.215_803_76 * b;
+    let m_ = l - 0.105_561_346 * a - 0.063_854_17 * b;
+    let s_ = l - 0.089_484_18 * a - 1.291_485_5 * b;
+
+    let (l, m, s) = (l_.powi(3), m_.powi(3), s_.powi(3));
+
+    let channel = |linear: f32| linear_to_srgb(linear.clamp(0.0, 1.0));
+
+    D2D1_COLOR_F {
+        r: channel(4.076_741_7 * l - 3.307_711_6 * m + 0.230_969_94 * s),
+        g: channel(-1.268_438 * l + 2.609_757_4 * m - 0.341_319_38 * s),
+        b: channel(-0.004_196_086_3 * l - 0.703_418_6 * m + 1.707_614_7 * s),
+        a: alpha.clamp(0.0, 1.0),
+    }
+}
+
+/// Converts a linear light channel into sRGB encoding.
+///
+/// # Parameters
+/// - `channel`: The linear channel value, ranging from 0.0 to 1.0.
+///
+/// # Returns
+/// The sRGB channel value, ranging from 0.0 to 1.0.
+pub(crate) fn linear_to_srgb(channel: f32) -> f32 {
+    if channel <= 0.0031308 {
+        channel * 12.92
+    } else {
+        1.055 * channel.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Converts a straight alpha color into a premultiplied alpha color.
+///
+/// # Parameters
+/// - `color`: The color with straight (unassociated) alpha.
+///
+/// # Returns
+/// The color with each of its red, green and blue channels multiplied by its alpha.
+pub(crate) fn premultiply(color: D2D1_COLOR_F) -> D2D1_COLOR_F {
+    D2D1_COLOR_F {
+        r: color.r * color.a,
+        g: color.g * color.a,
+        b: color.b * color.a,
+        a: color.a,
+    }
+}
+
+/// Converts a premultiplied alpha color into a straight alpha color.
+///
+/// # Parameters
+/// - `color`: The color with premultiplied alpha.
+///
+/// # Returns
+/// The color with each of its red, green and blue channels divided by its alpha. Fully
+/// transparent colors become transparent black, since their channels cannot be recovered.
+pub(crate) fn unpremultiply(color: D2D1_COLOR_F) -> D2D1_COLOR_F {
+    if color.a == 0.0 {
+        return D2D1_COLOR_F::default();
+    }
+
+    D2D1_COLOR_F {
+        r: color.r / color.a,
+        g: color.g / color.a,
+        b: color.b / color.a,
+        a: color.a,
+    }
+}
+
+/// Checks that every channel of a color is finite and within 0.0 to 1.0.
+///
+/// # Parameters
+/// - `color`: The color to check.
+///
+/// # Returns
+/// An `ErrorKind::InvalidData` error naming the first invalid channel.
+pub(crate) fn validate_color(color: &D2D1_COLOR_F) -> Result<()> {
+    let channels = [
+        ("red", color.r),
+        ("green", color.g),
+        ("blue", color.b),
+        ("alpha", color.a),
+    ];
+
+    match channels
+        .iter()
+        .find(|(_, value)| !(0.0..=1.0).contains(value))
+    {
+        Some((name, value)) => Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("The {} channel {} is not within 0.0 to 1.0", name, value),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Strips the first matching prefix and the first matching suffix from a string.
+///
+/// Prefixes and suffixes are tried in order and only the first match of each is removed, so a
+/// suffix list such as `["grad", "rad"]` must list longer suffixes sharing an ending first.
+///
+/// # Parameters
+/// - `input`: The string to strip.
+/// - `prefixes`: The prefixes to try, in order.
+/// - `suffixes`: The suffixes to try, in order.
+///
+/// # Returns
+/// A tuple of the remaining string, the prefix that was stripped and the suffix that was
+/// stripped, if any.
+pub(crate) fn strip_string<'a, 'b>(
+    input: &'a str,
+    prefixes: &[&'b str],
+    suffixes: &[&'b str],
+) -> (&'a str, Option<&'b str>, Option<&'b str>) {
+    let mut output = input;
+
+    let prefix = prefixes
+        .iter()
+        .find(|prefix| output.starts_with(**prefix))
+        .copied();
+    if let Some(prefix) = prefix {
+        output = &output[prefix.len()..];
+    }
+
+    let suffix = suffixes
+        .iter()
+        .find(|suffix| output.ends_with(**suffix))
+        .copied();
+    if let Some(suffix) = suffix {
+        output = &output[..output.len() - suffix.len()];
+    }
+
+    (output, prefix, suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn color(r: f32, g: f32, b: f32) -> D2D1_COLOR_F {
+        D2D1_COLOR_F { r, g, b, a: 1.0 }
+    }
+
+    fn assert_close(a: f32, b: f32) {
+        assert!((a - b).abs() < 1e-3, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn saturate_and_desaturate_adjust_saturation() {
+        // hsl(0, 50%, 50%)
+        let input = color(0.75, 0.25, 0.25);
+        assert_close(d2d1_to_hsla(input).s, 50.0);
+
+        assert_close(d2d1_to_hsla(saturate(input, 50.0)).s, 75.0);
+        assert_close(d2d1_to_hsla(desaturate(input, 50.0)).s, 25.0);
+        assert_close(d2d1_to_hsla(saturate(input, 500.0)).s, 100.0);
+        assert_close(d2d1_to_hsla(desaturate(input, 500.0)).s, 0.0);
+    }
+
+    #[test]
+    fn saturating_gray_is_a_no_op() {
+        let gray = color(0.5, 0.5, 0.5);
+
+        for adjusted in [saturate(gray, 50.0), desaturate(gray, 50.0)] {
+            assert_close(adjusted.r, gray.r);
+            assert_close(adjusted.g, gray.g);
+            assert_close(adjusted.b, gray.b);
+        }
+    }
+
+    #[test]
+    fn strips_multi_character_suffixes() {
+        let units = ["deg", "grad", "rad", "turn"];
+
+        assert_eq!(
+            strip_string("90deg", &[], &units),
+            ("90", None, Some("deg"))
+        );
+        assert_eq!(
+            strip_string("40grad", &[], &units),
+            ("40", None, Some("grad"))
+        );
+        assert_eq!(strip_string("90", &[], &units), ("90", None, None));
+    }
+
+    #[test]
+    fn strips_first_matching_prefix_and_suffix() {
+        assert_eq!(
+            strip_string("rgba(1, 2, 3)", &["rgba(", "rgb("], &[")"]),
+            ("1, 2, 3", Some("rgba("), Some(")"))
+        );
+        assert_eq!(
+            strip_string("#ffffff", &["#", "##"], &["f", "ff"]),
+            ("fffff", Some("#"), Some("f"))
+        );
+    }
+
+    #[test]
+    fn linear_adjustments_diverge_on_mid_gray() {
+        let gray = color(0.5, 0.5, 0.5);
+        let (dark, dark_linear) = (darken(gray, 50.0), darken_linear(gray, 50.0));
+        let (light, light_linear) = (lighten(gray, 50.0), lighten_linear(gray, 50.0));
+
+        assert_close(dark.r, 0.25);
+        assert_close(dark_linear.r, 0.361);
+        assert_close(light.r, 0.75);
+        assert!(light_linear.r < light.r && light_linear.r > gray.r);
+        for adjusted in [dark, dark_linear, light, light_linear] {
+            assert_close(adjusted.g, adjusted.r);
+            assert_close(adjusted.b, adjusted.r);
+            assert_eq!(adjusted.a, 1.0);
+        }
+    }
+}